@@ -0,0 +1,377 @@
+use std::collections::{BTreeMap, HashSet};
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use clap::Args;
+use homematic_rs::{GetLinksFlags, HomeMaticClient, Paramset};
+use log::warn;
+use serde::Deserialize;
+use serde_json::Value as JsonValue;
+use xrs_xmlrpc::value::Value;
+
+use crate::backup::{
+    archive_paramset, typed_value_from_json, value_to_json, values_equal, ArchivedParamset,
+};
+use crate::param::description_key;
+use crate::{confirm, Format};
+
+#[derive(Args)]
+pub struct ApplyCommand {
+    /// Desired state file (YAML or TOML)
+    file: PathBuf,
+
+    /// Only print the plan
+    #[arg(long)]
+    dry_run: bool,
+
+    /// Do not ask for confirmation
+    #[arg(short, long)]
+    yes: bool,
+}
+
+/// Desired configuration of devices and links.
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct DesiredState {
+    /// Configuration of all devices by device type.
+    #[serde(default)]
+    types: BTreeMap<String, DesiredDevice>,
+
+    /// Configuration by device address, overrides the configuration of the device type.
+    #[serde(default)]
+    devices: BTreeMap<String, DesiredDevice>,
+
+    #[serde(default)]
+    links: Vec<DesiredLink>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct DesiredDevice {
+    /// MASTER paramset of the device.
+    #[serde(default)]
+    master: ArchivedParamset,
+
+    /// MASTER paramsets by channel index.
+    #[serde(default)]
+    channels: BTreeMap<String, DesiredChannel>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct DesiredChannel {
+    #[serde(default)]
+    master: ArchivedParamset,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct DesiredLink {
+    sender: String,
+    receiver: String,
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    sender_paramset: ArchivedParamset,
+    #[serde(default)]
+    receiver_paramset: ArchivedParamset,
+}
+
+impl DesiredState {
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error + 'static>> {
+        let content = fs::read_to_string(path)
+            .map_err(|err| format!("failed to read {}: {}", path.display(), err))?;
+        let invalid = |err: &dyn Error| format!("invalid state {}: {}", path.display(), err);
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => Ok(toml::from_str(&content).map_err(|err| invalid(&err))?),
+            Some("yaml") | Some("yml") => {
+                Ok(serde_yaml::from_str(&content).map_err(|err| invalid(&err))?)
+            }
+            _ => Err(format!("unknown file type: {}", path.display()).into()),
+        }
+    }
+}
+
+/// Change of a single parameter.
+struct Change {
+    key: String,
+    current: Option<JsonValue>,
+    desired: JsonValue,
+    value: Value<'static>,
+}
+
+enum Action {
+    AddLink {
+        sender: String,
+        receiver: String,
+        name: String,
+        description: String,
+    },
+    PutParamset {
+        address: String,
+        paramset_key: String,
+        changes: Vec<Change>,
+    },
+}
+
+impl ApplyCommand {
+    pub async fn exec(
+        &self,
+        client: &HomeMaticClient,
+        _format: Format,
+    ) -> Result<(), Box<dyn Error + 'static>> {
+        let state = DesiredState::load(&self.file)?;
+        let plan = plan(client, &state).await?;
+
+        if plan.is_empty() {
+            println!("Nothing to do");
+            return Ok(());
+        }
+        for action in &plan {
+            print_action(action);
+        }
+        if self.dry_run {
+            return Ok(());
+        }
+        if !self.yes && !confirm(&format!("Apply {} changes?", plan.len()))? {
+            return Err("aborted".into());
+        }
+
+        let total = plan.len();
+        let mut failed = 0;
+        let mut buf = String::new();
+        for action in plan {
+            let result = match &action {
+                Action::AddLink {
+                    sender,
+                    receiver,
+                    name,
+                    description,
+                } => {
+                    client
+                        .add_link(sender, receiver, name, description, &mut buf)
+                        .await
+                }
+                Action::PutParamset {
+                    address,
+                    paramset_key,
+                    changes,
+                } => {
+                    let values: Paramset = changes
+                        .iter()
+                        .map(|change| (change.key.clone(), change.value.clone()))
+                        .collect();
+                    client
+                        .put_paramset(address, paramset_key, values, &mut buf)
+                        .await
+                }
+            };
+            if let Err(err) = result {
+                warn!("Failed to apply {}: {}", action, err);
+                failed += 1;
+            }
+        }
+
+        if failed > 0 {
+            return Err(format!("{} of {} changes could not be applied", failed, total).into());
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Action::AddLink {
+                sender, receiver, ..
+            } => write!(f, "link {} -> {}", sender, receiver),
+            Action::PutParamset {
+                address,
+                paramset_key,
+                ..
+            } => write!(f, "{} {}", address, paramset_key),
+        }
+    }
+}
+
+fn print_action(action: &Action) {
+    match action {
+        Action::AddLink {
+            sender, receiver, ..
+        } => println!("+ link {} -> {}", sender, receiver),
+        Action::PutParamset {
+            address,
+            paramset_key,
+            changes,
+        } => {
+            println!("~ {} {}", address, paramset_key);
+            for change in changes {
+                println!(
+                    "    {}: {} -> {}",
+                    change.key,
+                    change
+                        .current
+                        .as_ref()
+                        .map(|value| value.to_string())
+                        .unwrap_or_else(|| "-".to_string()),
+                    change.desired
+                );
+            }
+        }
+    }
+}
+
+/// Compute the actions needed to reach the desired state.
+async fn plan(
+    client: &HomeMaticClient,
+    state: &DesiredState,
+) -> Result<Vec<Action>, Box<dyn Error + 'static>> {
+    let mut buf = String::new();
+    let devices = client.list_devices(&mut buf).await?;
+
+    let mut actions = vec![];
+    let mut errors = vec![];
+    let mut existing_links = HashSet::new();
+    for dev in devices.iter().filter(|dev| dev.parent.is_empty()) {
+        let mut master = ArchivedParamset::new();
+        let mut channels = BTreeMap::<String, ArchivedParamset>::new();
+        for desired in [state.types.get(&dev.ty), state.devices.get(&dev.address)]
+            .into_iter()
+            .flatten()
+        {
+            master.extend(desired.master.clone());
+            for (index, channel) in &desired.channels {
+                channels
+                    .entry(format!("{}:{}", dev.address, index))
+                    .or_default()
+                    .extend(channel.master.clone());
+            }
+        }
+
+        if !master.is_empty() {
+            channels.insert(dev.address.clone(), master);
+        }
+        for (address, desired) in channels {
+            match plan_paramset(client, &address, "MASTER", true, &desired).await {
+                Ok(Some(action)) => actions.push(action),
+                Ok(None) => {}
+                Err(err) => errors.push(format!("{} MASTER: {}", address, err)),
+            }
+        }
+
+        if !state.links.is_empty() {
+            for link in client
+                .get_links(&dev.address, GetLinksFlags::empty(), &mut buf)
+                .await?
+            {
+                existing_links.insert((link.sender, link.receiver));
+            }
+        }
+    }
+
+    for address in state.devices.keys() {
+        if !devices.iter().any(|dev| &dev.address == address) {
+            errors.push(format!("{}: unknown device", address));
+        }
+    }
+
+    for link in &state.links {
+        let exists = existing_links.contains(&(link.sender.clone(), link.receiver.clone()));
+        if !exists {
+            actions.push(Action::AddLink {
+                sender: link.sender.clone(),
+                receiver: link.receiver.clone(),
+                name: link.name.clone(),
+                description: link.description.clone(),
+            });
+        }
+
+        let paramsets = [
+            (&link.sender, &link.receiver, &link.sender_paramset),
+            (&link.receiver, &link.sender, &link.receiver_paramset),
+        ];
+        for (address, peer, desired) in paramsets {
+            if desired.is_empty() {
+                continue;
+            }
+            match plan_paramset(client, address, peer, exists, desired).await {
+                Ok(Some(action)) => actions.push(action),
+                Ok(None) => {}
+                Err(err) => errors.push(format!("{} link {}: {}", address, peer, err)),
+            }
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(format!("invalid desired state:\n  {}", errors.join("\n  ")).into());
+    }
+    Ok(actions)
+}
+
+/// Compare a desired paramset with the current paramset.
+///
+/// `exists` is false for paramsets of links that are created by the plan.
+async fn plan_paramset(
+    client: &HomeMaticClient,
+    address: &str,
+    paramset_key: &str,
+    exists: bool,
+    desired: &ArchivedParamset,
+) -> Result<Option<Action>, Box<dyn Error + 'static>> {
+    let mut buf = String::new();
+    let description = client
+        .get_paramset_description(address, description_key(paramset_key), &mut buf)
+        .await?;
+    let current = if exists {
+        archive_paramset(&client.get_paramset(address, paramset_key, &mut buf).await?)
+    } else {
+        ArchivedParamset::new()
+    };
+
+    let mut changes = vec![];
+    let mut errors = vec![];
+    for (key, value) in desired {
+        let pd = match description.get(key) {
+            Some(pd) if pd.is_writable() => pd,
+            Some(_) => {
+                errors.push(format!("parameter {} is not writable", key));
+                continue;
+            }
+            None => {
+                errors.push(format!("unknown parameter {}", key));
+                continue;
+            }
+        };
+        let typed = match typed_value_from_json(pd, value) {
+            Ok(typed) => typed,
+            Err(err) => {
+                errors.push(format!("{}: {}", key, err));
+                continue;
+            }
+        };
+        if !values_equal(current.get(key), Some(&value_to_json(&typed))) {
+            changes.push(Change {
+                key: key.clone(),
+                current: current.get(key).cloned(),
+                desired: value.clone(),
+                value: typed,
+            });
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(errors.join(", ").into());
+    }
+    if changes.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(Action::PutParamset {
+        address: address.to_string(),
+        paramset_key: paramset_key.to_string(),
+        changes,
+    }))
+}
@@ -0,0 +1,129 @@
+use crate::backup::{archive_paramset, Archive, ArchivedDevice, ArchivedLink, ARCHIVE_VERSION};
+use crate::Format;
+use clap::Args;
+use homematic_rs::{GetLinksFlags, HomeMaticClient};
+use log::{info, warn};
+use std::collections::HashSet;
+use std::error::Error;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(Args)]
+pub struct BackupCommand {
+    /// Archive file to write
+    file: PathBuf,
+}
+
+impl BackupCommand {
+    pub async fn exec(
+        &self,
+        client: &HomeMaticClient,
+        _format: Format,
+    ) -> Result<(), Box<dyn Error + 'static>> {
+        let mut buf = String::new();
+        let descriptions = client.list_devices(&mut buf).await?;
+
+        let mut devices = vec![];
+        let mut links = vec![];
+        let mut seen_links = HashSet::new();
+        let mut failed = 0;
+        for description in descriptions {
+            info!("Backing up {}", description.address);
+
+            let master = if description.paramsets.iter().any(|ps| ps == "MASTER") {
+                // e.g. an unreachable device, the others are still worth saving
+                match client
+                    .get_paramset(&description.address, "MASTER", &mut buf)
+                    .await
+                {
+                    Ok(paramset) => Some(archive_paramset(&paramset)),
+                    Err(err) => {
+                        warn!("Failed to read MASTER of {}: {}", description.address, err);
+                        failed += 1;
+                        None
+                    }
+                }
+            } else {
+                None
+            };
+
+            let metadata = match client
+                .get_all_metadata(&description.address, &mut buf)
+                .await
+            {
+                Ok(metadata) => archive_paramset(&metadata),
+                Err(err) => {
+                    warn!("No metadata for {}: {}", description.address, err);
+                    Default::default()
+                }
+            };
+
+            if description.parent.is_empty() {
+                let device_links = match client
+                    .get_links(&description.address, GetLinksFlags::empty(), &mut buf)
+                    .await
+                {
+                    Ok(device_links) => device_links,
+                    Err(err) => {
+                        warn!("Failed to read links of {}: {}", description.address, err);
+                        failed += 1;
+                        vec![]
+                    }
+                };
+                for link in device_links {
+                    if seen_links.insert((link.sender.clone(), link.receiver.clone())) {
+                        links.push(ArchivedLink {
+                            sender: link.sender,
+                            receiver: link.receiver,
+                            name: link.name,
+                            description: link.description,
+                            sender_paramset: None,
+                            receiver_paramset: None,
+                        });
+                    }
+                }
+            }
+
+            devices.push(ArchivedDevice {
+                description: description.into(),
+                master,
+                metadata,
+            });
+        }
+
+        for link in &mut links {
+            match client
+                .get_paramset(&link.sender, &link.receiver, &mut buf)
+                .await
+            {
+                Ok(paramset) => link.sender_paramset = Some(archive_paramset(&paramset)),
+                Err(err) => warn!("No sender paramset for {}: {}", link.sender, err),
+            }
+            match client
+                .get_paramset(&link.receiver, &link.sender, &mut buf)
+                .await
+            {
+                Ok(paramset) => link.receiver_paramset = Some(archive_paramset(&paramset)),
+                Err(err) => warn!("No receiver paramset for {}: {}", link.receiver, err),
+            }
+        }
+
+        let archive = Archive {
+            version: ARCHIVE_VERSION,
+            created: SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
+            devices,
+            links,
+        };
+
+        let file = File::create(&self.file)
+            .map_err(|err| format!("failed to create {}: {}", self.file.display(), err))?;
+        serde_json::to_writer_pretty(BufWriter::new(file), &archive)?;
+
+        if failed > 0 {
+            return Err(format!("{} paramsets or links could not be saved", failed).into());
+        }
+        Ok(())
+    }
+}
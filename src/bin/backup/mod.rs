@@ -0,0 +1,259 @@
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use xrs_xmlrpc::value::Value;
+
+use homematic_rs::{ParameterDescription, Paramset};
+
+use crate::device::inspect::OutputDeviceDescription;
+
+pub mod create;
+pub mod restore;
+
+/// Version of the archive format written by `backup`.
+pub const ARCHIVE_VERSION: u32 = 1;
+
+/// Paramset with values stored as JSON.
+pub type ArchivedParamset = BTreeMap<String, JsonValue>;
+
+/// Backup of all devices of an interface.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Archive {
+    pub version: u32,
+
+    /// Creation time in seconds since the Unix epoch.
+    pub created: u64,
+
+    pub devices: Vec<ArchivedDevice>,
+
+    pub links: Vec<ArchivedLink>,
+}
+
+/// Backup of a device or channel.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchivedDevice {
+    pub description: OutputDeviceDescription,
+
+    pub master: Option<ArchivedParamset>,
+
+    #[serde(default)]
+    pub metadata: ArchivedParamset,
+}
+
+/// Backup of a direct link.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchivedLink {
+    pub sender: String,
+    pub receiver: String,
+    pub name: String,
+    pub description: String,
+
+    /// Link paramset of the sending channel.
+    pub sender_paramset: Option<ArchivedParamset>,
+
+    /// Link paramset of the receiving channel.
+    pub receiver_paramset: Option<ArchivedParamset>,
+}
+
+impl Archive {
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error + 'static>> {
+        let file = File::open(path)
+            .map_err(|err| format!("failed to open {}: {}", path.display(), err))?;
+        let archive: Archive = serde_json::from_reader(BufReader::new(file))?;
+        if archive.version > ARCHIVE_VERSION {
+            return Err(format!("unsupported archive version {}", archive.version).into());
+        }
+        Ok(archive)
+    }
+
+    /// Backup of the device or channel `address`.
+    pub fn device(&self, address: &str) -> Option<&ArchivedDevice> {
+        self.devices
+            .iter()
+            .find(|dev| dev.description.address == address)
+    }
+}
+
+pub fn value_to_json(value: &Value) -> JsonValue {
+    match value {
+        Value::Int(value) => JsonValue::from(*value),
+        Value::Bool(value) => JsonValue::Bool(*value),
+        Value::Double(value) => JsonValue::from(*value),
+        Value::String(value) => JsonValue::String(value.to_string()),
+        other => serde_json::to_value(other).unwrap_or_default(),
+    }
+}
+
+pub fn value_from_json(value: &JsonValue) -> Option<Value<'static>> {
+    match value {
+        JsonValue::Bool(value) => Some(Value::Bool(*value)),
+        JsonValue::Number(value) => match value.as_i64() {
+            Some(value) => Some(Value::Int(value as i32)),
+            None => value.as_f64().map(Value::Double),
+        },
+        JsonValue::String(value) => Some(Value::String(Cow::Owned(value.clone()))),
+        _ => None,
+    }
+}
+
+/// Convert `value` to the value type of the parameter `description`.
+///
+/// Fails for values of another type and numbers out of MIN and MAX that are no special value.
+/// Enum values may be given by index or label.
+pub fn typed_value_from_json(
+    description: &ParameterDescription,
+    value: &JsonValue,
+) -> Result<Value<'static>, String> {
+    let mismatch = || format!("invalid value {}", value);
+    let out_of_range = |min: &dyn fmt::Display, max: &dyn fmt::Display| {
+        format!("{} is out of range [{}, {}]", value, min, max)
+    };
+
+    match (description, value) {
+        (ParameterDescription::Float(pd), JsonValue::Number(number)) => {
+            let number = number.as_f64().ok_or_else(mismatch)?;
+            let special = pd
+                .special
+                .iter()
+                .flatten()
+                .any(|s| s.value == number as f32);
+            if !special && ((number as f32) < pd.min || (number as f32) > pd.max) {
+                return Err(out_of_range(&pd.min, &pd.max));
+            }
+            Ok(Value::Double(number))
+        }
+        (ParameterDescription::Integer(pd), JsonValue::Number(number)) => {
+            let number = number
+                .as_i64()
+                .and_then(|number| i32::try_from(number).ok())
+                .ok_or_else(mismatch)?;
+            let special = pd.special.iter().flatten().any(|s| s.value == number);
+            if !special && !(pd.min..=pd.max).contains(&number) {
+                return Err(out_of_range(&pd.min, &pd.max));
+            }
+            Ok(Value::Int(number))
+        }
+        (ParameterDescription::Enum(pd), JsonValue::Number(number)) => {
+            let index = number
+                .as_i64()
+                .and_then(|index| i32::try_from(index).ok())
+                .ok_or_else(mismatch)?;
+            if !(pd.min..=pd.max).contains(&index) {
+                return Err(out_of_range(&pd.min, &pd.max));
+            }
+            Ok(Value::Int(index))
+        }
+        (ParameterDescription::Enum(pd), JsonValue::String(label)) => pd
+            .index(label)
+            .map(Value::Int)
+            .ok_or_else(|| format!("unknown label {}", label)),
+        (ParameterDescription::Bool(_), JsonValue::Bool(value))
+        | (ParameterDescription::Action(_), JsonValue::Bool(value)) => Ok(Value::Bool(*value)),
+        (ParameterDescription::Bool(_), JsonValue::Number(number))
+        | (ParameterDescription::Action(_), JsonValue::Number(number)) => match number.as_i64() {
+            Some(0) => Ok(Value::Bool(false)),
+            Some(1) => Ok(Value::Bool(true)),
+            _ => Err(mismatch()),
+        },
+        (ParameterDescription::String(_), JsonValue::String(value)) => {
+            Ok(Value::String(Cow::Owned(value.clone())))
+        }
+        _ => Err(mismatch()),
+    }
+}
+
+/// Compare values, tolerating the precision loss of single precision floats.
+pub fn values_equal(a: Option<&JsonValue>, b: Option<&JsonValue>) -> bool {
+    match (a, b) {
+        (Some(JsonValue::Number(a)), Some(JsonValue::Number(b))) => {
+            match (a.as_f64(), b.as_f64()) {
+                (Some(a), Some(b)) => (a - b).abs() <= 1e-6 * a.abs().max(b.abs()).max(1.0),
+                _ => a == b,
+            }
+        }
+        (a, b) => a == b,
+    }
+}
+
+pub fn archive_paramset(paramset: &Paramset) -> ArchivedParamset {
+    paramset
+        .iter()
+        .map(|(key, value)| (key.clone(), value_to_json(value)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn description(value: JsonValue) -> ParameterDescription {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn typed_values() {
+        let float = description(json!({
+            "TYPE": "FLOAT", "OPERATIONS": 7, "FLAGS": 1, "DEFAULT": 0.0, "MIN": 0.0, "MAX": 1.0,
+            "UNIT": "100%", "SPECIAL": [{"ID": "NOT_USED", "VALUE": 1.005}]
+        }));
+        assert!(
+            matches!(typed_value_from_json(&float, &json!(0.5)), Ok(Value::Double(v)) if v == 0.5)
+        );
+        assert!(typed_value_from_json(&float, &json!(1.005)).is_ok());
+        assert!(typed_value_from_json(&float, &json!(1.5)).is_err());
+        assert!(typed_value_from_json(&float, &json!("0.5")).is_err());
+
+        let integer = description(json!({
+            "TYPE": "INTEGER", "OPERATIONS": 3, "FLAGS": 1, "DEFAULT": 0, "MIN": 0, "MAX": 60,
+            "UNIT": "s"
+        }));
+        assert!(matches!(
+            typed_value_from_json(&integer, &json!(30)),
+            Ok(Value::Int(30))
+        ));
+        assert!(typed_value_from_json(&integer, &json!(61)).is_err());
+        assert!(typed_value_from_json(&integer, &json!(4294967296i64)).is_err());
+        assert!(typed_value_from_json(&integer, &json!(true)).is_err());
+
+        let enumeration = description(json!({
+            "TYPE": "ENUM", "OPERATIONS": 3, "FLAGS": 1, "DEFAULT": 0, "MIN": 0, "MAX": 1,
+            "UNIT": "", "VALUE_LIST": ["OFF", "ON"]
+        }));
+        assert!(matches!(
+            typed_value_from_json(&enumeration, &json!("ON")),
+            Ok(Value::Int(1))
+        ));
+        assert!(matches!(
+            typed_value_from_json(&enumeration, &json!(0)),
+            Ok(Value::Int(0))
+        ));
+        assert!(typed_value_from_json(&enumeration, &json!(2)).is_err());
+        assert!(typed_value_from_json(&enumeration, &json!("AUTO")).is_err());
+
+        let boolean = description(json!({
+            "TYPE": "BOOL", "OPERATIONS": 3, "FLAGS": 1, "DEFAULT": false, "MIN": false,
+            "MAX": true, "UNIT": ""
+        }));
+        assert!(matches!(
+            typed_value_from_json(&boolean, &json!(true)),
+            Ok(Value::Bool(true))
+        ));
+        assert!(matches!(
+            typed_value_from_json(&boolean, &json!(0)),
+            Ok(Value::Bool(false))
+        ));
+        assert!(typed_value_from_json(&boolean, &json!(2)).is_err());
+        assert!(typed_value_from_json(&boolean, &json!("true")).is_err());
+    }
+}
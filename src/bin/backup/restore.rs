@@ -0,0 +1,203 @@
+use crate::backup::{typed_value_from_json, Archive, ArchivedParamset};
+use crate::param::description_key;
+use crate::Format;
+use clap::Args;
+use homematic_rs::{GetLinksFlags, HomeMaticClient, Paramset};
+use log::{info, warn};
+use std::collections::HashSet;
+use std::error::Error;
+use std::path::PathBuf;
+
+#[derive(Args)]
+pub struct RestoreCommand {
+    /// Archive file to read
+    file: PathBuf,
+
+    /// Only restore this device
+    #[arg(long)]
+    device: Option<String>,
+
+    /// Restore the device to this (replacement) device address
+    #[arg(long, requires = "device")]
+    to: Option<String>,
+
+    /// Do not restore links
+    #[arg(long)]
+    no_links: bool,
+
+    /// Only print what would be restored
+    #[arg(long)]
+    dry_run: bool,
+}
+
+impl RestoreCommand {
+    pub async fn exec(
+        &self,
+        client: &HomeMaticClient,
+        _format: Format,
+    ) -> Result<(), Box<dyn Error + 'static>> {
+        let archive = Archive::load(&self.file)?;
+        if let Some(device) = &self.device {
+            if archive.device(device).is_none() {
+                return Err(format!("{} is not part of the archive", device).into());
+            }
+        }
+
+        let mut failed = 0;
+        for dev in &archive.devices {
+            let address = &dev.description.address;
+            if !self.is_selected(address) {
+                continue;
+            }
+            if let Some(master) = &dev.master {
+                let target = self.map_address(address);
+                if let Err(err) = self
+                    .restore_paramset(client, &target, "MASTER", master)
+                    .await
+                {
+                    warn!("Failed to restore MASTER of {}: {}", target, err);
+                    failed += 1;
+                }
+            }
+        }
+
+        if !self.no_links {
+            let mut buf = String::new();
+            let existing = existing_links(client, &mut buf).await?;
+            for link in &archive.links {
+                if !self.is_selected(&link.sender) && !self.is_selected(&link.receiver) {
+                    continue;
+                }
+                let sender = self.map_address(&link.sender);
+                let receiver = self.map_address(&link.receiver);
+
+                if !existing.contains(&(sender.clone(), receiver.clone())) {
+                    println!("add link {} -> {}", sender, receiver);
+                    if !self.dry_run {
+                        if let Err(err) = client
+                            .add_link(&sender, &receiver, &link.name, &link.description, &mut buf)
+                            .await
+                        {
+                            warn!("Failed to add link {} -> {}: {}", sender, receiver, err);
+                            failed += 1;
+                            continue;
+                        }
+                    }
+                }
+
+                let paramsets = [
+                    (&sender, &receiver, &link.sender_paramset),
+                    (&receiver, &sender, &link.receiver_paramset),
+                ];
+                for (address, peer, paramset) in paramsets {
+                    if let Some(paramset) = paramset {
+                        if let Err(err) =
+                            self.restore_paramset(client, address, peer, paramset).await
+                        {
+                            warn!("Failed to restore link {} of {}: {}", peer, address, err);
+                            failed += 1;
+                        }
+                    }
+                }
+            }
+        }
+
+        if failed > 0 {
+            return Err(format!("{} paramsets or links could not be restored", failed).into());
+        }
+        Ok(())
+    }
+
+    /// Device or channel `address` belongs to the selected device.
+    fn is_selected(&self, address: &str) -> bool {
+        match &self.device {
+            Some(device) => device_address(address) == device,
+            None => true,
+        }
+    }
+
+    /// Address of `address` on the target device.
+    fn map_address(&self, address: &str) -> String {
+        match (&self.device, &self.to) {
+            (Some(device), Some(to)) if device_address(address) == device => {
+                format!("{}{}", to, &address[device.len()..])
+            }
+            _ => address.to_string(),
+        }
+    }
+
+    /// Write the writable parameters of `paramset` to `address`.
+    async fn restore_paramset(
+        &self,
+        client: &HomeMaticClient,
+        address: &str,
+        paramset_key: &str,
+        paramset: &ArchivedParamset,
+    ) -> Result<(), Box<dyn Error + 'static>> {
+        let mut buf = String::new();
+        let description = client
+            .get_paramset_description(address, description_key(paramset_key), &mut buf)
+            .await?;
+
+        let mut values = Paramset::new();
+        for (key, value) in paramset {
+            match description.get(key) {
+                Some(pd) if pd.is_writable() => match typed_value_from_json(pd, value) {
+                    Ok(value) => {
+                        values.insert(key.clone(), value);
+                    }
+                    Err(err) => warn!("Skipping {} of {}: {}", key, address, err),
+                },
+                Some(_) => {}
+                None => warn!("Skipping {} of {}: unknown parameter", key, address),
+            }
+        }
+        if values.is_empty() {
+            return Ok(());
+        }
+
+        info!(
+            "Restoring {} {} ({} values)",
+            address,
+            paramset_key,
+            values.len()
+        );
+        if self.dry_run {
+            println!(
+                "restore {} {}: {} values",
+                address,
+                paramset_key,
+                values.len()
+            );
+            return Ok(());
+        }
+        client
+            .put_paramset(address, paramset_key, values, &mut buf)
+            .await?;
+        Ok(())
+    }
+}
+
+/// Device part of a device or channel address.
+fn device_address(address: &str) -> &str {
+    address.split(':').next().unwrap_or(address)
+}
+
+async fn existing_links(
+    client: &HomeMaticClient,
+    buf: &mut String,
+) -> Result<HashSet<(String, String)>, Box<dyn Error + 'static>> {
+    let mut links = HashSet::new();
+    for dev in client.list_devices(buf).await? {
+        if !dev.parent.is_empty() {
+            continue;
+        }
+        for link in client
+            .get_links(&dev.address, GetLinksFlags::empty(), buf)
+            .await?
+        {
+            links.insert((link.sender, link.receiver));
+        }
+    }
+    Ok(links)
+}
@@ -0,0 +1,233 @@
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt;
+
+use clap::Args;
+use comfy_table::Table;
+use homematic_rs::{DeviceDescription, HomeMaticClient, MaintenanceStatus};
+use serde::Serialize;
+
+use crate::Format;
+
+#[derive(Args)]
+pub struct BatteryCommand {
+    /// only show devices reporting a low battery
+    #[arg(long)]
+    low: bool,
+
+    /// number of devices read with one request
+    #[arg(long, default_value_t = 50)]
+    batch: usize,
+}
+
+/// Batteries of a device model.
+#[derive(Clone, Copy)]
+struct Battery {
+    count: u8,
+    size: &'static str,
+}
+
+impl fmt::Display for Battery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}x {}", self.count, self.size)
+    }
+}
+
+const AA: &str = "LR6/AA";
+const AAA: &str = "LR03/AAA";
+const LR44: &str = "LR44";
+const CR2032: &str = "CR2032";
+
+/// Count and size of the batteries of common battery-powered device models.
+const BATTERIES: &[(&str, u8, &str)] = &[
+    ("HM-CC-RT-DN", 2, AA),
+    ("HM-CC-TC", 2, AA),
+    ("HM-TC-IT-WM-W-EU", 2, AAA),
+    ("HM-WDS10-TH-O", 2, AA),
+    ("HM-WDS40-TH-I-2", 2, AAA),
+    ("HM-Sec-SC-2", 2, LR44),
+    ("HM-Sec-SCo", 1, AAA),
+    ("HM-Sec-RHS", 1, AA),
+    ("HM-Sec-MDIR-2", 3, AA),
+    ("HM-Sen-MDIR-WM55", 2, AAA),
+    ("HM-PB-2-WM55-2", 2, AAA),
+    ("HM-PB-6-WM55", 2, AAA),
+    ("HM-RC-4-2", 1, AAA),
+    ("HM-RC-Key4-2", 1, AAA),
+    ("HM-RC-4-3", 1, AAA),
+    ("HmIP-eTRV", 2, AA),
+    ("HmIP-eTRV-2", 2, AA),
+    ("HmIP-eTRV-B", 2, AA),
+    ("HmIP-eTRV-C", 2, AA),
+    ("HmIP-WTH-2", 2, AAA),
+    ("HmIP-STH", 2, AAA),
+    ("HmIP-STHD", 2, AAA),
+    ("HmIP-STHO", 2, AA),
+    ("HmIP-SWDO", 1, AAA),
+    ("HmIP-SWDO-I", 2, AAA),
+    ("HmIP-SWDO-PL", 2, AAA),
+    ("HmIP-SRH", 1, AAA),
+    ("HmIP-SWD", 2, AAA),
+    ("HmIP-SMI", 2, AA),
+    ("HmIP-SMI55", 2, AAA),
+    ("HmIP-SMO", 2, AA),
+    ("HmIP-SPI", 2, AA),
+    ("HmIP-WRC2", 1, AAA),
+    ("HmIP-WRC6", 2, AAA),
+    ("HmIP-BRC2", 1, AAA),
+    ("HmIP-RC8", 2, AAA),
+    ("HmIP-KRC4", 1, CR2032),
+    ("HmIP-KRCA", 1, CR2032),
+];
+
+fn battery(device_type: &str) -> Option<Battery> {
+    BATTERIES
+        .iter()
+        .find(|(ty, _, _)| *ty == device_type)
+        .map(|(_, count, size)| Battery {
+            count: *count,
+            size,
+        })
+}
+
+/// Devices of known battery-powered models and devices that are not always listening.
+fn is_battery_powered(device: &DeviceDescription) -> bool {
+    if battery(&device.ty).is_some() {
+        return true;
+    }
+    match &device.rx_mode {
+        Some(rx_mode) => {
+            !rx_mode.always() && (rx_mode.burst() || rx_mode.config() || rx_mode.wakeup())
+        }
+        None => false,
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct OutputBattery {
+    #[serde(rename = "type")]
+    ty: String,
+    address: String,
+    battery: Option<String>,
+    voltage: Option<f64>,
+    low: Option<bool>,
+    unreach: Option<bool>,
+    error: Option<String>,
+    #[serde(skip)]
+    cell_voltage: Option<f64>,
+}
+
+impl OutputBattery {
+    /// Low batteries first, then unknown states, then by voltage per cell.
+    fn urgency(&self, other: &Self) -> Ordering {
+        let rank = |battery: &Self| match battery.low {
+            Some(true) => 0,
+            None => 1,
+            Some(false) => 2,
+        };
+        rank(self)
+            .cmp(&rank(other))
+            .then_with(|| match (self.cell_voltage, other.cell_voltage) {
+                (Some(a), Some(b)) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            })
+            .then_with(|| self.address.cmp(&other.address))
+    }
+}
+
+impl BatteryCommand {
+    pub async fn exec(
+        &self,
+        client: &HomeMaticClient,
+        format: Format,
+    ) -> Result<(), Box<dyn Error + 'static>> {
+        let mut buf = String::new();
+        let descriptions = client.list_devices(&mut buf).await?;
+        let devices: Vec<(&DeviceDescription, &DeviceDescription)> = descriptions
+            .iter()
+            .filter(|dev| dev.is_device() && is_battery_powered(dev))
+            .filter_map(|dev| Some((dev, dev.maintenance_channel(&descriptions)?)))
+            .collect();
+
+        let mut batteries = vec![];
+        for chunk in devices.chunks(self.batch.max(1)) {
+            let requests: Vec<(&str, &str)> = chunk
+                .iter()
+                .map(|(_, channel)| (channel.address.as_str(), "VALUES"))
+                .collect();
+            let mut buf = String::new();
+            let paramsets = client.get_paramsets(&requests, &mut buf).await?;
+            for ((device, _), paramset) in chunk.iter().zip(paramsets) {
+                let status = paramset.map(|paramset| MaintenanceStatus::from_paramset(&paramset));
+                batteries.push(output_battery(device, status));
+            }
+        }
+
+        if self.low {
+            batteries.retain(|battery| battery.low == Some(true));
+        }
+        batteries.sort_by(|a, b| a.urgency(b));
+
+        match format {
+            Format::Table => {
+                let mut table = Table::new();
+                table
+                    .set_header(vec!["TYPE", "ADDRESS", "BATTERY", "VOLTAGE", "LOW"])
+                    .load_preset(comfy_table::presets::NOTHING);
+                for battery in &batteries {
+                    table.add_row(vec![
+                        battery.ty.clone(),
+                        battery.address.clone(),
+                        battery.battery.clone().unwrap_or_else(|| "-".to_string()),
+                        battery
+                            .voltage
+                            .map(|voltage| format!("{:.2} V", voltage))
+                            .unwrap_or_else(|| "-".to_string()),
+                        match (&battery.error, battery.low) {
+                            (Some(error), _) => error.clone(),
+                            (None, Some(true)) => "yes".to_string(),
+                            (None, Some(false)) => "no".to_string(),
+                            (None, None) => "-".to_string(),
+                        },
+                    ]);
+                }
+                println!("{table}");
+            }
+            Format::Json => serde_json::to_writer_pretty(std::io::stdout(), &batteries)?,
+        }
+
+        Ok(())
+    }
+}
+
+fn output_battery(
+    device: &DeviceDescription,
+    status: Result<MaintenanceStatus, String>,
+) -> OutputBattery {
+    let battery = battery(&device.ty);
+    let mut output = OutputBattery {
+        ty: device.ty.clone(),
+        address: device.address.clone(),
+        battery: battery.map(|battery| battery.to_string()),
+        voltage: None,
+        low: None,
+        unreach: None,
+        error: None,
+        cell_voltage: None,
+    };
+    match status {
+        Ok(status) => {
+            output.voltage = status.operating_voltage;
+            output.low = status.low_battery;
+            output.unreach = Some(status.unreach);
+            output.cell_voltage = battery
+                .zip(status.operating_voltage)
+                .map(|(battery, voltage)| voltage / f64::from(battery.count));
+        }
+        Err(error) => output.error = Some(error),
+    }
+    output
+}
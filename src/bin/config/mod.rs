@@ -0,0 +1,288 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::{env, fs};
+
+use clap::Args;
+use homematic_rs::HomeMaticClient;
+use serde::Deserialize;
+use xrs_xmlrpc::client::reqwest::XmlRpcClientBuilder;
+
+/// Options selecting the CCU to connect to.
+#[derive(Args)]
+pub struct ConnectionArgs {
+    /// configuration file [default: <config dir>/homematicctl/config.toml]
+    #[arg(long, global = true, env = "HM_CONFIG")]
+    config: Option<PathBuf>,
+
+    /// connection profile of the configuration file
+    #[arg(long, global = true, env = "HM_PROFILE")]
+    profile: Option<String>,
+
+    /// interface of the profile to connect to
+    #[arg(long, global = true, env = "HM_INTERFACE")]
+    interface: Option<String>,
+
+    /// XML-RPC URL of the interface, overrides the profile
+    #[arg(long, global = true, env = "HM_URL")]
+    url: Option<String>,
+
+    /// user name, overrides the profile
+    #[arg(long, global = true, env = "HM_USERNAME")]
+    username: Option<String>,
+}
+
+/// Content of the configuration file.
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct Config {
+    /// Profile used when none is selected.
+    default_profile: Option<String>,
+
+    #[serde(default)]
+    profiles: HashMap<String, Profile>,
+}
+
+/// Named connection profile.
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct Profile {
+    /// XML-RPC URL used when no interface is selected.
+    url: Option<String>,
+
+    /// XML-RPC URLs by interface name (e.g. `rf`, `hmip`, `wired`).
+    #[serde(default)]
+    interfaces: HashMap<String, String>,
+
+    /// Interface used when none is selected.
+    default_interface: Option<String>,
+
+    username: Option<String>,
+
+    password: Option<String>,
+
+    /// Shell command printing the password.
+    password_command: Option<String>,
+
+    #[serde(default)]
+    tls: TlsConfig,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct TlsConfig {
+    /// PEM file with an additional trusted CA certificate.
+    ca_certificate: Option<PathBuf>,
+
+    /// Accept any server certificate (e.g. the self-signed certificate of the CCU).
+    #[serde(default)]
+    insecure: bool,
+}
+
+impl Config {
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error + 'static>> {
+        let content = fs::read_to_string(path)
+            .map_err(|err| format!("failed to read {}: {}", path.display(), err))?;
+        Ok(toml::from_str(&content)
+            .map_err(|err| format!("invalid config {}: {}", path.display(), err))?)
+    }
+
+    fn default_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("homematicctl").join("config.toml"))
+    }
+}
+
+impl Profile {
+    fn url(&self, interface: Option<&str>) -> Result<Option<String>, Box<dyn Error + 'static>> {
+        if let Some(interface) = interface.or(self.default_interface.as_deref()) {
+            return match self.interfaces.get(interface) {
+                Some(url) => Ok(Some(url.clone())),
+                None => Err(format!("unknown interface: {}", interface).into()),
+            };
+        }
+
+        if self.url.is_some() {
+            Ok(self.url.clone())
+        } else if self.interfaces.len() == 1 {
+            Ok(self.interfaces.values().next().cloned())
+        } else if self.interfaces.is_empty() {
+            Ok(None)
+        } else {
+            Err("profile has multiple interfaces, select one with --interface".into())
+        }
+    }
+
+    fn password(&self) -> Result<Option<String>, Box<dyn Error + 'static>> {
+        if let Some(command) = &self.password_command {
+            let output = Command::new("sh").arg("-c").arg(command).output()?;
+            if !output.status.success() {
+                return Err(format!("password command failed: {}", output.status).into());
+            }
+            let password = String::from_utf8(output.stdout)?;
+            return Ok(Some(password.trim_end_matches(['\r', '\n']).to_string()));
+        }
+
+        Ok(self.password.clone())
+    }
+}
+
+impl ConnectionArgs {
+    /// Build a client from the command line, the environment and the selected profile.
+    pub fn connect(&self) -> Result<HomeMaticClient, Box<dyn Error + 'static>> {
+        let config = match &self.config {
+            Some(path) => Config::load(path)?,
+            None => match Config::default_path() {
+                Some(path) if path.exists() => Config::load(&path)?,
+                _ => Config::default(),
+            },
+        };
+
+        let mut config_profiles = config.profiles;
+        let profile = match self.profile.as_ref().or(config.default_profile.as_ref()) {
+            Some(name) => config_profiles
+                .remove(name)
+                .ok_or_else(|| format!("unknown profile: {}", name))?,
+            None => Profile::default(),
+        };
+
+        let url = match &self.url {
+            Some(url) => url.clone(),
+            None => profile
+                .url(self.interface.as_deref())?
+                .ok_or("no URL given, use --url, HM_URL or a profile")?,
+        };
+
+        let mut builder = XmlRpcClientBuilder::new(url);
+        if let Some(username) = self.username.as_ref().or(profile.username.as_ref()) {
+            let password = match env::var("HM_PASSWORD") {
+                Ok(password) => Some(password),
+                Err(_) => profile.password()?,
+            };
+            builder = builder.basic_auth(username.clone(), password);
+        }
+
+        if profile.tls.insecure || profile.tls.ca_certificate.is_some() {
+            let mut http =
+                reqwest::Client::builder().danger_accept_invalid_certs(profile.tls.insecure);
+            if let Some(path) = &profile.tls.ca_certificate {
+                let pem = fs::read(path)
+                    .map_err(|err| format!("failed to read {}: {}", path.display(), err))?;
+                http = http.add_root_certificate(reqwest::Certificate::from_pem(&pem)?);
+            }
+            builder = builder.client(http.build()?);
+        }
+
+        Ok(HomeMaticClient::new(builder.build()?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(content: &str) -> Config {
+        toml::from_str(content).unwrap()
+    }
+
+    #[test]
+    fn load_profiles() {
+        let config = config(
+            r#"
+            default-profile = "home"
+
+            [profiles.home]
+            username = "admin"
+            password = "secret"
+            default-interface = "hmip"
+
+            [profiles.home.interfaces]
+            rf = "http://ccu:2001"
+            hmip = "http://ccu:2010"
+
+            [profiles.home.tls]
+            insecure = true
+
+            [profiles.office]
+            url = "https://office:42001"
+            "#,
+        );
+        assert_eq!(config.default_profile.as_deref(), Some("home"));
+        assert_eq!(config.profiles.len(), 2);
+
+        let home = &config.profiles["home"];
+        assert_eq!(home.username.as_deref(), Some("admin"));
+        assert_eq!(home.interfaces.len(), 2);
+        assert!(home.tls.insecure);
+        assert!(home.tls.ca_certificate.is_none());
+
+        let office = &config.profiles["office"];
+        assert!(office.interfaces.is_empty());
+        assert!(!office.tls.insecure);
+    }
+
+    #[test]
+    fn profile_url() {
+        let config = config(
+            r#"
+            [profiles.single]
+            interfaces = { rf = "http://ccu:2001" }
+
+            [profiles.multiple]
+            interfaces = { rf = "http://ccu:2001", hmip = "http://ccu:2010" }
+
+            [profiles.default]
+            default-interface = "hmip"
+            interfaces = { rf = "http://ccu:2001", hmip = "http://ccu:2010" }
+
+            [profiles.url]
+            url = "http://ccu:2001"
+            "#,
+        );
+        let url = |profile: &str, interface: Option<&str>| {
+            config.profiles[profile]
+                .url(interface)
+                .map_err(|err| err.to_string())
+        };
+
+        assert_eq!(url("single", None), Ok(Some("http://ccu:2001".to_string())));
+        assert!(url("multiple", None).is_err());
+        assert_eq!(
+            url("multiple", Some("hmip")),
+            Ok(Some("http://ccu:2010".to_string()))
+        );
+        assert_eq!(
+            url("default", None),
+            Ok(Some("http://ccu:2010".to_string()))
+        );
+        assert_eq!(
+            url("default", Some("rf")),
+            Ok(Some("http://ccu:2001".to_string()))
+        );
+        assert_eq!(url("url", None), Ok(Some("http://ccu:2001".to_string())));
+        assert!(url("url", Some("wired")).is_err());
+        assert_eq!(Profile::default().url(None).ok(), Some(None));
+    }
+
+    #[test]
+    fn password_command() {
+        let profile = Profile {
+            password: Some("ignored".to_string()),
+            password_command: Some("printf 'secret\\n'".to_string()),
+            ..Profile::default()
+        };
+        assert_eq!(profile.password().ok(), Some(Some("secret".to_string())));
+
+        let profile = Profile {
+            password_command: Some("exit 1".to_string()),
+            ..Profile::default()
+        };
+        assert!(profile.password().is_err());
+
+        let profile = Profile {
+            password: Some("secret".to_string()),
+            ..Profile::default()
+        };
+        assert_eq!(profile.password().ok(), Some(Some("secret".to_string())));
+    }
+}
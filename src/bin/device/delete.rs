@@ -0,0 +1,97 @@
+use crate::{confirm, Format};
+use clap::Args;
+use homematic_rs::{DeviceDeleteFlags, GetLinksFlags, HomeMaticClient};
+use std::error::Error;
+
+#[derive(Args)]
+pub struct DeleteDeviceCommand {
+    /// Device address
+    device: String,
+
+    /// Reset the device to factory state before deletion
+    #[arg(long)]
+    reset: bool,
+
+    /// Delete the device even when it is not reachable
+    #[arg(long)]
+    force: bool,
+
+    /// Delete the device at the next opportunity when it is not reachable
+    #[arg(long)]
+    defer: bool,
+
+    /// Do not ask for confirmation
+    #[arg(short, long)]
+    yes: bool,
+
+    /// Abort a pending deferred deletion instead
+    #[arg(long, conflicts_with_all = ["reset", "force", "defer", "yes"])]
+    abort: bool,
+}
+
+impl DeleteDeviceCommand {
+    pub async fn exec(
+        &self,
+        client: &HomeMaticClient,
+        _format: Format,
+    ) -> Result<(), Box<dyn Error + 'static>> {
+        let mut buf = String::new();
+
+        if self.abort {
+            client.abort_delete_device(&self.device, &mut buf).await?;
+            println!("Aborted deletion of {}", self.device);
+            return Ok(());
+        }
+
+        let device = client
+            .get_device_description(&self.device, &mut buf)
+            .await?;
+        if !device.parent.is_empty() {
+            return Err(format!("{} is a channel, not a device", self.device).into());
+        }
+        if !device.is_deletable() {
+            return Err(format!("{} ({}) is not deletable", device.address, device.ty).into());
+        }
+
+        println!("Device {} ({})", device.address, device.ty);
+
+        let links = client
+            .get_links(&self.device, GetLinksFlags::empty(), &mut buf)
+            .await?;
+        if links.is_empty() {
+            println!("No links");
+        } else {
+            println!("Links that will be removed:");
+            for link in &links {
+                println!("    {} -> {} {}", link.sender, link.receiver, link.name);
+            }
+        }
+
+        let prefix = format!("{}:", device.address);
+        let config_pending = client
+            .get_service_messages(&mut buf)
+            .await?
+            .iter()
+            .any(|sm| {
+                sm.id() == "CONFIG_PENDING"
+                    && (sm.address() == device.address || sm.address().starts_with(&prefix))
+            });
+        if config_pending {
+            println!("Warning: configuration of the device is still pending");
+        }
+
+        if !self.yes && !confirm(&format!("Delete {}?", device.address))? {
+            return Err("aborted".into());
+        }
+
+        let mut flags = DeviceDeleteFlags::empty();
+        flags.set(DeviceDeleteFlags::RESET, self.reset);
+        flags.set(DeviceDeleteFlags::FORCE, self.force);
+        flags.set(DeviceDeleteFlags::DEFER, self.defer);
+        client
+            .delete_device(&device.address, flags, &mut buf)
+            .await?;
+
+        Ok(())
+    }
+}
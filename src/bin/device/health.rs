@@ -0,0 +1,153 @@
+use crate::Format;
+use clap::Args;
+use comfy_table::Table;
+use futures::stream::{self, StreamExt};
+use homematic_rs::{HomeMaticClient, MaintenanceStatus};
+use serde::Serialize;
+use std::error::Error;
+
+#[derive(Args)]
+pub struct DeviceHealthCommand {
+    /// only show devices that are unreachable, low on battery, over their duty cycle or have
+    /// pending configuration
+    #[arg(long)]
+    problems: bool,
+
+    /// number of devices read concurrently
+    #[arg(long, default_value_t = 4)]
+    parallel: usize,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct OutputDeviceHealth {
+    #[serde(rename = "type")]
+    ty: String,
+    address: String,
+    unreach: Option<bool>,
+    sticky_unreach: Option<bool>,
+    low_battery: Option<bool>,
+    rssi_device: Option<i32>,
+    rssi_peer: Option<i32>,
+    duty_cycle: Option<bool>,
+    config_pending: Option<bool>,
+    error: Option<String>,
+    #[serde(skip)]
+    has_problem: bool,
+}
+
+impl DeviceHealthCommand {
+    pub async fn exec(
+        &self,
+        client: &HomeMaticClient,
+        format: Format,
+    ) -> Result<(), Box<dyn Error + 'static>> {
+        let mut buf = String::new();
+        let descriptions = client.list_devices(&mut buf).await?;
+        let devices = descriptions
+            .iter()
+            .filter(|dev| dev.is_device() && dev.maintenance_channel(&descriptions).is_some());
+
+        let mut health: Vec<OutputDeviceHealth> = stream::iter(devices)
+            .map(|dev| async move {
+                let mut buf = String::new();
+                let status = match client.get_maintenance_status(dev, &mut buf).await {
+                    Ok(Some(status)) => Ok(status),
+                    Ok(None) => Err("no maintenance channel".to_string()),
+                    Err(err) => Err(err.to_string()),
+                };
+                output_health(dev.ty.clone(), dev.address.clone(), status)
+            })
+            .buffer_unordered(self.parallel.max(1))
+            .filter(|health| std::future::ready(!self.problems || health.has_problem))
+            .collect()
+            .await;
+        health.sort_by(|a, b| a.address.cmp(&b.address));
+
+        match format {
+            Format::Table => {
+                let mut table = Table::new();
+                table
+                    .set_header(vec![
+                        "TYPE",
+                        "ADDRESS",
+                        "UNREACH",
+                        "LOW BATTERY",
+                        "RSSI DEVICE",
+                        "RSSI PEER",
+                        "DUTY CYCLE",
+                        "CONFIG PENDING",
+                    ])
+                    .load_preset(comfy_table::presets::NOTHING);
+                for dev in &health {
+                    if let Some(error) = &dev.error {
+                        table.add_row(vec![dev.ty.as_str(), dev.address.as_str(), error]);
+                        continue;
+                    }
+                    table.add_row(vec![
+                        dev.ty.clone(),
+                        dev.address.clone(),
+                        format_flag(dev.unreach),
+                        format_flag(dev.low_battery),
+                        format_rssi(dev.rssi_device),
+                        format_rssi(dev.rssi_peer),
+                        format_flag(dev.duty_cycle),
+                        format_flag(dev.config_pending),
+                    ]);
+                }
+                println!("{table}");
+            }
+            Format::Json => serde_json::to_writer_pretty(std::io::stdout(), &health)?,
+        }
+
+        Ok(())
+    }
+}
+
+fn output_health(
+    ty: String,
+    address: String,
+    status: Result<MaintenanceStatus, String>,
+) -> OutputDeviceHealth {
+    match status {
+        Ok(status) => OutputDeviceHealth {
+            ty,
+            address,
+            unreach: Some(status.unreach),
+            sticky_unreach: status.sticky_unreach,
+            low_battery: status.low_battery,
+            rssi_device: status.rssi_device,
+            rssi_peer: status.rssi_peer,
+            duty_cycle: status.duty_cycle,
+            config_pending: Some(status.config_pending),
+            error: None,
+            has_problem: status.has_problem(),
+        },
+        Err(error) => OutputDeviceHealth {
+            ty,
+            address,
+            unreach: None,
+            sticky_unreach: None,
+            low_battery: None,
+            rssi_device: None,
+            rssi_peer: None,
+            duty_cycle: None,
+            config_pending: None,
+            error: Some(error),
+            has_problem: true,
+        },
+    }
+}
+
+fn format_flag(flag: Option<bool>) -> String {
+    match flag {
+        Some(true) => "yes".to_string(),
+        Some(false) => "no".to_string(),
+        None => "-".to_string(),
+    }
+}
+
+fn format_rssi(rssi: Option<i32>) -> String {
+    rssi.map(|rssi| format!("{} dBm", rssi))
+        .unwrap_or_else(|| "-".to_string())
+}
@@ -1,7 +1,7 @@
 use crate::Format;
 use clap::Args;
 use homematic_rs::{ChannelDirection, DeviceDescription, HomeMaticClient, RxMode};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::error::Error;
 
 #[derive(Args)]
@@ -11,7 +11,7 @@ pub struct InspectDeviceCommand {
 }
 
 /// Specifies the direction (transmit or receive) of this channel in a direct link.
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub enum OutputChannelDirection {
     None,
@@ -19,7 +19,7 @@ pub enum OutputChannelDirection {
     Receiver,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct OutputRxMode {
     pub always: bool,
@@ -29,7 +29,7 @@ pub struct OutputRxMode {
     pub lazy_config: bool,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct OutputDeviceDescription {
     /// Type of device
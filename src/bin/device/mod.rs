@@ -1,9 +1,17 @@
+use crate::device::delete::DeleteDeviceCommand;
+use crate::device::health::DeviceHealthCommand;
 use crate::device::inspect::InspectDeviceCommand;
 use crate::device::list::ListDevicesCommand;
+use crate::device::replace::ReplaceDeviceCommand;
+use crate::device::sync::SyncDeviceCommand;
 use clap::Subcommand;
 
+pub mod delete;
+pub mod health;
 pub mod inspect;
 pub mod list;
+pub mod replace;
+pub mod sync;
 
 #[derive(Subcommand)]
 pub enum DeviceCommand {
@@ -11,4 +19,12 @@ pub enum DeviceCommand {
     List(ListDevicesCommand),
     /// Describe device or channel
     Inspect(InspectDeviceCommand),
+    /// Delete a device
+    Delete(DeleteDeviceCommand),
+    /// Replace a device by a newly paired device of the same type
+    Replace(ReplaceDeviceCommand),
+    /// Transfer pending configuration to devices
+    Sync(SyncDeviceCommand),
+    /// Show reachability, battery and reception of all devices
+    Health(DeviceHealthCommand),
 }
@@ -0,0 +1,69 @@
+use crate::{confirm, Format};
+use clap::Args;
+use homematic_rs::HomeMaticClient;
+use std::error::Error;
+
+#[derive(Args)]
+pub struct ReplaceDeviceCommand {
+    /// Address of the device to replace
+    old_device: String,
+
+    /// Address of the newly paired device
+    new_device: String,
+
+    /// Do not ask for confirmation
+    #[arg(short, long)]
+    yes: bool,
+}
+
+impl ReplaceDeviceCommand {
+    pub async fn exec(
+        &self,
+        client: &HomeMaticClient,
+        _format: Format,
+    ) -> Result<(), Box<dyn Error + 'static>> {
+        let mut buf = String::new();
+        let old_device = client
+            .get_device_description(&self.old_device, &mut buf)
+            .await?;
+        let new_device = client
+            .get_device_description(&self.new_device, &mut buf)
+            .await?;
+
+        if old_device.ty != new_device.ty {
+            return Err(format!(
+                "device types differ: {} is {}, {} is {}",
+                old_device.address, old_device.ty, new_device.address, new_device.ty
+            )
+            .into());
+        }
+
+        let replaceable = client
+            .list_replaceable_devices(&new_device.address, &mut buf)
+            .await?;
+        if !replaceable
+            .iter()
+            .any(|dev| dev.address == old_device.address)
+        {
+            return Err(format!(
+                "{} cannot be replaced by {}",
+                old_device.address, new_device.address
+            )
+            .into());
+        }
+
+        println!(
+            "Replace {} by {} ({})",
+            old_device.address, new_device.address, new_device.ty
+        );
+        if !self.yes && !confirm("Transfer links and configuration?")? {
+            return Err("aborted".into());
+        }
+
+        client
+            .replace_device(&old_device.address, &new_device.address, &mut buf)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,126 @@
+use crate::Format;
+use clap::Args;
+use homematic_rs::{HomeMaticClient, RxMode};
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::time::{Duration, Instant};
+
+#[derive(Args)]
+pub struct SyncDeviceCommand {
+    /// Device address
+    #[arg(required_unless_present = "all_pending")]
+    device: Option<String>,
+
+    /// Sync all devices with pending configuration
+    #[arg(long, conflicts_with = "device")]
+    all_pending: bool,
+
+    /// Maximum time to wait per device in seconds
+    #[arg(long, default_value_t = 120)]
+    timeout: u64,
+}
+
+/// Devices with a CONFIG_PENDING service message.
+async fn config_pending_devices(
+    client: &HomeMaticClient,
+) -> Result<BTreeSet<String>, Box<dyn Error + 'static>> {
+    let mut buf = String::new();
+    Ok(client
+        .get_service_messages(&mut buf)
+        .await?
+        .iter()
+        .filter(|sm| sm.id() == "CONFIG_PENDING")
+        .map(|sm| {
+            sm.address()
+                .split(':')
+                .next()
+                .unwrap_or_default()
+                .to_string()
+        })
+        .collect())
+}
+
+/// Hint how the device can be reached for its configuration.
+fn reachability_hint(rx_mode: &RxMode) -> Option<&'static str> {
+    if rx_mode.always() || rx_mode.burst() {
+        None
+    } else if rx_mode.config() && !rx_mode.wakeup() && !rx_mode.lazy_config() {
+        Some("press the config button of the device")
+    } else {
+        Some("operate the device (e.g. press a button) to wake it up")
+    }
+}
+
+impl SyncDeviceCommand {
+    pub async fn exec(
+        &self,
+        client: &HomeMaticClient,
+        _format: Format,
+    ) -> Result<(), Box<dyn Error + 'static>> {
+        let devices: Vec<String> = match &self.device {
+            Some(device) => vec![device.clone()],
+            None => config_pending_devices(client).await?.into_iter().collect(),
+        };
+        if devices.is_empty() {
+            println!("No devices with pending configuration");
+            return Ok(());
+        }
+
+        let mut failed = vec![];
+        for device in &devices {
+            if let Err(err) = self.sync(client, device).await {
+                eprintln!("{}: {}", device, err);
+                failed.push(device.as_str());
+            }
+        }
+
+        if failed.is_empty() {
+            Ok(())
+        } else {
+            Err(format!("sync failed for {}", failed.join(", ")).into())
+        }
+    }
+
+    async fn sync(
+        &self,
+        client: &HomeMaticClient,
+        device: &str,
+    ) -> Result<(), Box<dyn Error + 'static>> {
+        let mut buf = String::new();
+        let description = client.get_device_description(device, &mut buf).await?;
+        // for sleeping devices CONFIG_PENDING shows up only some time after the restore
+        let mut seen_pending = config_pending_devices(client).await?.contains(device);
+
+        client.restore_config_to_device(device, &mut buf).await?;
+        let hint = description.rx_mode.as_ref().and_then(reachability_hint);
+        match hint {
+            Some(hint) => println!("{} ({}): {}", device, description.ty, hint),
+            None => println!(
+                "{} ({}): transferring configuration",
+                device, description.ty
+            ),
+        }
+
+        // always listening devices may receive the configuration before the first poll, the
+        // others must report CONFIG_PENDING first
+        let listening = hint.is_none();
+        let deadline = Instant::now() + Duration::from_secs(self.timeout);
+        loop {
+            tokio::time::sleep(Duration::from_secs(2)).await;
+
+            let pending = config_pending_devices(client).await?.contains(device);
+            seen_pending |= pending;
+            if !pending && (seen_pending || listening) {
+                println!("{}: configuration transferred", device);
+                return Ok(());
+            }
+            if Instant::now() >= deadline {
+                if !pending {
+                    println!("{}: no pending configuration reported", device);
+                    return Ok(());
+                }
+                return Err("timeout while waiting for the configuration transfer".into());
+            }
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use std::error::Error;
+use std::net::SocketAddr;
+
+use clap::Args;
+use homematic_rs::{Event, EventServer, HomeMaticClient};
+
+/// Options for receiving events from the CCU.
+#[derive(Args)]
+pub struct CallbackArgs {
+    /// local address to listen on for callbacks of the CCU
+    #[arg(long, default_value = "0.0.0.0:0")]
+    pub listen: SocketAddr,
+
+    /// host name or IP address under which the CCU reaches this machine
+    #[arg(long)]
+    pub callback_host: String,
+}
+
+/// Event subscription registered with the CCU.
+pub struct Subscription<'a> {
+    client: &'a HomeMaticClient,
+    server: EventServer,
+    url: String,
+}
+
+impl<'a> Subscription<'a> {
+    pub async fn start(
+        client: &'a HomeMaticClient,
+        args: &CallbackArgs,
+    ) -> Result<Subscription<'a>, Box<dyn Error + 'static>> {
+        let server = EventServer::bind(args.listen).await?;
+        let url = format!(
+            "http://{}:{}",
+            args.callback_host,
+            server.local_addr().port()
+        );
+
+        let mut buf = String::new();
+        client
+            .init(
+                &url,
+                &format!("{}-{}", env!("CARGO_BIN_NAME"), std::process::id()),
+                &mut buf,
+            )
+            .await?;
+
+        Ok(Self {
+            client,
+            server,
+            url,
+        })
+    }
+
+    pub async fn next(&mut self) -> Option<Event> {
+        self.server.next().await
+    }
+
+    pub async fn stop(self) -> Result<(), Box<dyn Error + 'static>> {
+        let mut buf = String::new();
+        self.client.deinit(&self.url, &mut buf).await?;
+        Ok(())
+    }
+}
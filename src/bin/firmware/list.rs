@@ -0,0 +1,89 @@
+use crate::Format;
+use clap::Args;
+use comfy_table::Table;
+use homematic_rs::HomeMaticClient;
+use serde::Serialize;
+use std::error::Error;
+
+#[derive(Args)]
+pub struct ListFirmwareCommand {
+    /// Show all devices, not only those with pending updates
+    #[arg(long)]
+    all: bool,
+
+    /// Re-read the deployed firmware files first
+    #[arg(long)]
+    refresh: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct OutputFirmware {
+    #[serde(rename = "type")]
+    ty: String,
+    address: String,
+    firmware: Option<String>,
+    available_firmware: Option<String>,
+    updatable: bool,
+}
+
+impl OutputFirmware {
+    fn update_available(&self) -> bool {
+        self.updatable
+            && self.available_firmware.is_some()
+            && self.available_firmware != self.firmware
+    }
+}
+
+impl ListFirmwareCommand {
+    pub async fn exec(
+        &self,
+        client: &HomeMaticClient,
+        format: Format,
+    ) -> Result<(), Box<dyn Error + 'static>> {
+        let mut buf = String::new();
+        if self.refresh {
+            client
+                .refresh_deployed_device_firmware_list(&mut buf)
+                .await?;
+        }
+
+        let devices: Vec<OutputFirmware> = client
+            .list_devices(&mut buf)
+            .await?
+            .into_iter()
+            .filter(|dev| dev.parent.is_empty())
+            .map(|dev| OutputFirmware {
+                ty: dev.ty,
+                address: dev.address,
+                firmware: dev.firmware,
+                available_firmware: dev.available_firmware,
+                updatable: dev.updatable.unwrap_or_default(),
+            })
+            .filter(|dev| self.all || dev.update_available())
+            .collect();
+
+        match format {
+            Format::Table => {
+                let mut table = Table::new();
+                table
+                    .set_header(vec!["TYPE", "ADDRESS", "FIRMWARE", "AVAILABLE"])
+                    .load_preset(comfy_table::presets::NOTHING);
+
+                for dev in &devices {
+                    table.add_row(vec![
+                        &dev.ty,
+                        &dev.address,
+                        dev.firmware.as_deref().unwrap_or(""),
+                        dev.available_firmware.as_deref().unwrap_or(""),
+                    ]);
+                }
+
+                println!("{table}");
+            }
+            Format::Json => serde_json::to_writer_pretty(std::io::stdout(), &devices)?,
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,15 @@
+use clap::Subcommand;
+
+use crate::firmware::list::ListFirmwareCommand;
+use crate::firmware::update::UpdateFirmwareCommand;
+
+pub mod list;
+pub mod update;
+
+#[derive(Subcommand)]
+pub enum FirmwareCommand {
+    /// Lists devices with available firmware updates
+    List(ListFirmwareCommand),
+    /// Update the firmware of devices
+    Update(UpdateFirmwareCommand),
+}
@@ -0,0 +1,156 @@
+use crate::Format;
+use clap::Args;
+use homematic_rs::{DeviceDescription, HomeMaticClient};
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::error::Error;
+use std::time::{Duration, Instant};
+
+/// Service messages reported while a firmware update is in progress.
+const UPDATE_MESSAGES: &[&str] = &["UPDATE_PENDING", "DEVICE_IN_BOOTLOADER"];
+
+#[derive(Args)]
+pub struct UpdateFirmwareCommand {
+    /// Device addresses
+    #[arg(required = true)]
+    devices: Vec<String>,
+
+    /// Wait until the update service messages of the devices are cleared
+    #[arg(long)]
+    wait: bool,
+
+    /// Maximum time to wait in seconds
+    #[arg(long, default_value_t = 1800)]
+    timeout: u64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct OutputUpdateResult<'a> {
+    address: &'a str,
+    started: bool,
+}
+
+impl UpdateFirmwareCommand {
+    pub async fn exec(
+        &self,
+        client: &HomeMaticClient,
+        format: Format,
+    ) -> Result<(), Box<dyn Error + 'static>> {
+        let mut buf = String::new();
+        let devices: Vec<&str> = self.devices.iter().map(|dev| dev as &str).collect();
+        let started = client.update_firmware(&devices, &mut buf).await?;
+
+        let results: Vec<OutputUpdateResult> = devices
+            .iter()
+            .zip(started.into_iter().chain(std::iter::repeat(false)))
+            .map(|(address, started)| OutputUpdateResult { address, started })
+            .collect();
+
+        match format {
+            Format::Table => {
+                for result in &results {
+                    let state = if result.started { "started" } else { "failed" };
+                    println!("{}: {}", result.address, state);
+                }
+            }
+            Format::Json => println!("{}", serde_json::to_string(&results)?),
+        }
+
+        if self.wait {
+            let pending = results
+                .iter()
+                .filter(|result| result.started)
+                .map(|result| result.address)
+                .collect();
+            self.wait_for_updates(client, pending).await?;
+        }
+
+        let failed: Vec<&str> = results
+            .iter()
+            .filter(|result| !result.started)
+            .map(|result| result.address)
+            .collect();
+        if !failed.is_empty() {
+            return Err(
+                format!("firmware update failed to start for {}", failed.join(", ")).into(),
+            );
+        }
+        Ok(())
+    }
+
+    /// Poll until every device in `pending` finished its update.
+    ///
+    /// A device is done once it runs its available firmware, or once its update service messages
+    /// were seen and cleared again.
+    async fn wait_for_updates(
+        &self,
+        client: &HomeMaticClient,
+        mut pending: BTreeSet<&str>,
+    ) -> Result<(), Box<dyn Error + 'static>> {
+        let deadline = Instant::now() + Duration::from_secs(self.timeout);
+        let mut last_state = HashMap::<String, String>::new();
+        let mut seen = HashSet::<String>::new();
+        let mut buf = String::new();
+
+        loop {
+            let mut state = HashMap::<String, String>::new();
+            for sm in client.get_service_messages(&mut buf).await? {
+                let device = sm.address().split(':').next().unwrap_or_default();
+                if pending.contains(device) && UPDATE_MESSAGES.contains(&sm.id()) {
+                    state.insert(device.to_string(), sm.id().to_string());
+                }
+            }
+
+            for (device, id) in &state {
+                if last_state.get(device) != Some(id) {
+                    eprintln!("{}: {}", device, id);
+                }
+            }
+            seen.extend(state.keys().cloned());
+
+            let mut done = vec![];
+            for device in pending.iter().filter(|dev| !state.contains_key(**dev)) {
+                // the update may finish between two polls without any message being seen
+                let description = match client.get_device_description(device, &mut buf).await {
+                    Ok(description) => description,
+                    Err(_) => continue,
+                };
+                if seen.contains(*device) || firmware_installed(&description) {
+                    eprintln!(
+                        "{}: done, firmware {}",
+                        device,
+                        description.firmware.as_deref().unwrap_or("unknown")
+                    );
+                    done.push(*device);
+                }
+            }
+            for device in done {
+                pending.remove(device);
+            }
+
+            if pending.is_empty() {
+                return Ok(());
+            }
+            if Instant::now() >= deadline {
+                let pending: Vec<&str> = pending.into_iter().collect();
+                return Err(format!(
+                    "timeout while waiting for firmware updates of {}",
+                    pending.join(", ")
+                )
+                .into());
+            }
+
+            last_state = state;
+            tokio::time::sleep(Duration::from_secs(10)).await;
+        }
+    }
+}
+
+/// The device runs the firmware that was offered for it.
+fn firmware_installed(description: &DeviceDescription) -> bool {
+    match (&description.firmware, &description.available_firmware) {
+        (Some(firmware), Some(available)) => firmware == available,
+        _ => false,
+    }
+}
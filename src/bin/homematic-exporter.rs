@@ -0,0 +1,570 @@
+use std::collections::{BTreeMap, HashMap};
+use std::convert::Infallible;
+use std::error::Error;
+use std::fmt::Write;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use clap::Parser;
+use hyper::header::CONTENT_TYPE;
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Request, Response, Server, StatusCode};
+use log::{info, warn};
+use xrs_xmlrpc::value::Value;
+
+use crate::config::ConnectionArgs;
+use crate::events::{CallbackArgs, Subscription};
+use homematic_rs::{DeviceDescription, Event, HomeMaticClient};
+
+mod config;
+mod events;
+
+/// Export values of HomeMatic devices as Prometheus metrics.
+#[derive(Parser)]
+#[command(author, version, about, long_about = None)]
+struct Cli {
+    /// verbose level
+    #[arg(short, long, action = clap::ArgAction::Count)]
+    verbose: u8,
+
+    /// address to serve /metrics on
+    #[arg(long, default_value = "0.0.0.0:9591")]
+    listen: SocketAddr,
+
+    /// host name or IP address under which the CCU reaches this machine, enables events
+    #[arg(long)]
+    callback_host: Option<String>,
+
+    /// local address to listen on for callbacks of the CCU
+    #[arg(long, default_value = "0.0.0.0:0")]
+    callback_listen: SocketAddr,
+
+    /// seconds between polls of values (without events), service messages and duty cycles
+    #[arg(long, default_value_t = 60)]
+    interval: u64,
+
+    /// number of channels read with one request
+    #[arg(long, default_value_t = 50)]
+    batch: usize,
+
+    #[command(flatten)]
+    connection: ConnectionArgs,
+}
+
+/// Types of a channel used as metric labels.
+struct ChannelLabels {
+    device_type: String,
+    channel_type: String,
+}
+
+struct DutyCycleGauge {
+    address: String,
+    description: String,
+    connected: bool,
+    level: Option<f64>,
+}
+
+/// Current state served on /metrics.
+#[derive(Default)]
+struct Metrics {
+    up: bool,
+    channels: HashMap<String, ChannelLabels>,
+    values: BTreeMap<(String, String), f64>,
+    service_messages: BTreeMap<String, usize>,
+    duty_cycles: Vec<DutyCycleGauge>,
+}
+
+impl Metrics {
+    fn render(&self) -> String {
+        let mut out = String::new();
+
+        writeln!(
+            out,
+            "# HELP homematic_up Whether the last request to the CCU succeeded."
+        )
+        .ok();
+        writeln!(out, "# TYPE homematic_up gauge").ok();
+        writeln!(out, "homematic_up {}", u8::from(self.up)).ok();
+
+        writeln!(
+            out,
+            "# HELP homematic_value Current value of a VALUES parameter."
+        )
+        .ok();
+        writeln!(out, "# TYPE homematic_value gauge").ok();
+        for ((address, parameter), value) in &self.values {
+            let (device_type, channel_type) = match self.channels.get(address) {
+                Some(labels) => (labels.device_type.as_str(), labels.channel_type.as_str()),
+                None => ("", ""),
+            };
+            writeln!(
+                out,
+                "homematic_value{{address=\"{}\",device_type=\"{}\",channel_type=\"{}\",parameter=\"{}\"}} {}",
+                escape(address),
+                escape(device_type),
+                escape(channel_type),
+                escape(parameter),
+                value
+            )
+            .ok();
+        }
+
+        writeln!(
+            out,
+            "# HELP homematic_service_messages Number of active service messages by type."
+        )
+        .ok();
+        writeln!(out, "# TYPE homematic_service_messages gauge").ok();
+        for (ty, count) in &self.service_messages {
+            writeln!(
+                out,
+                "homematic_service_messages{{type=\"{}\"}} {}",
+                escape(ty),
+                count
+            )
+            .ok();
+        }
+
+        writeln!(
+            out,
+            "# HELP homematic_duty_cycle_percent Used duty cycle of a radio interface."
+        )
+        .ok();
+        writeln!(out, "# TYPE homematic_duty_cycle_percent gauge").ok();
+        for interface in &self.duty_cycles {
+            if let Some(level) = interface.level {
+                writeln!(
+                    out,
+                    "homematic_duty_cycle_percent{{address=\"{}\",description=\"{}\"}} {}",
+                    escape(&interface.address),
+                    escape(&interface.description),
+                    level
+                )
+                .ok();
+            }
+        }
+
+        writeln!(
+            out,
+            "# HELP homematic_interface_connected Whether a radio interface is connected."
+        )
+        .ok();
+        writeln!(out, "# TYPE homematic_interface_connected gauge").ok();
+        for interface in &self.duty_cycles {
+            writeln!(
+                out,
+                "homematic_interface_connected{{address=\"{}\",description=\"{}\"}} {}",
+                escape(&interface.address),
+                escape(&interface.description),
+                u8::from(interface.connected)
+            )
+            .ok();
+        }
+
+        out
+    }
+}
+
+/// Escape a Prometheus label value.
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Numeric value of a parameter, booleans are exported as 0 and 1.
+fn numeric_value(value: &Value) -> Option<f64> {
+    match value {
+        Value::Double(value) => Some(*value),
+        Value::Int(value) => Some(f64::from(*value)),
+        Value::Bool(value) => Some(if *value { 1.0 } else { 0.0 }),
+        _ => None,
+    }
+}
+
+type SharedMetrics = Arc<Mutex<Metrics>>;
+
+async fn serve_metrics(addr: SocketAddr, metrics: SharedMetrics) -> Result<(), hyper::Error> {
+    let make_svc = make_service_fn(move |_conn| {
+        let metrics = metrics.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |req: Request<Body>| {
+                let metrics = metrics.clone();
+                async move {
+                    let response = if req.uri().path() == "/metrics" {
+                        let body = metrics.lock().unwrap().render();
+                        let mut response = Response::new(Body::from(body));
+                        response
+                            .headers_mut()
+                            .insert(CONTENT_TYPE, "text/plain; version=0.0.4".parse().unwrap());
+                        response
+                    } else {
+                        let mut response = Response::new(Body::from("not found"));
+                        *response.status_mut() = StatusCode::NOT_FOUND;
+                        response
+                    };
+                    Ok::<_, Infallible>(response)
+                }
+            }))
+        }
+    });
+
+    Server::try_bind(&addr)?.serve(make_svc).await
+}
+
+/// Channels with a VALUES paramset.
+async fn load_channels(
+    client: &HomeMaticClient,
+) -> Result<HashMap<String, ChannelLabels>, Box<dyn Error + 'static>> {
+    let mut buf = String::new();
+    let descriptions = client.list_devices(&mut buf).await?;
+    let device_types: HashMap<&str, &str> = descriptions
+        .iter()
+        .filter(|dev| dev.is_device())
+        .map(|dev| (dev.address.as_str(), dev.ty.as_str()))
+        .collect();
+
+    Ok(descriptions
+        .iter()
+        .filter(|dev| !dev.is_device() && dev.paramsets.iter().any(|ps| ps == "VALUES"))
+        .map(|dev: &DeviceDescription| {
+            let device_type = dev
+                .parent_type
+                .as_deref()
+                .or_else(|| device_types.get(dev.parent.as_str()).copied())
+                .unwrap_or_default();
+            (
+                dev.address.clone(),
+                ChannelLabels {
+                    device_type: device_type.to_string(),
+                    channel_type: dev.ty.clone(),
+                },
+            )
+        })
+        .collect())
+}
+
+/// Read the VALUES paramsets of all channels with `system.multicall`.
+async fn poll_values(
+    client: &HomeMaticClient,
+    addresses: &[String],
+    batch: usize,
+) -> Result<BTreeMap<(String, String), f64>, Box<dyn Error + 'static>> {
+    let mut values = BTreeMap::new();
+    for chunk in addresses.chunks(batch.max(1)) {
+        let requests: Vec<(&str, &str)> = chunk
+            .iter()
+            .map(|address| (address.as_str(), "VALUES"))
+            .collect();
+        let mut buf = String::new();
+        let paramsets = client.get_paramsets(&requests, &mut buf).await?;
+        for (address, paramset) in chunk.iter().zip(paramsets) {
+            let paramset = match paramset {
+                Ok(paramset) => paramset,
+                Err(err) => {
+                    warn!("Failed to read {}: {}", address, err);
+                    continue;
+                }
+            };
+            for (key, value) in &paramset {
+                if let Some(value) = numeric_value(value) {
+                    values.insert((address.clone(), key.clone()), value);
+                }
+            }
+        }
+    }
+    Ok(values)
+}
+
+/// Refresh service message counts.
+async fn poll_service_messages(
+    client: &HomeMaticClient,
+    metrics: &SharedMetrics,
+) -> Result<(), Box<dyn Error + 'static>> {
+    let mut buf = String::new();
+    let mut service_messages = BTreeMap::new();
+    for message in client.get_service_messages(&mut buf).await? {
+        *service_messages
+            .entry(message.id().to_string())
+            .or_default() += 1;
+    }
+
+    metrics.lock().unwrap().service_messages = service_messages;
+    Ok(())
+}
+
+/// Refresh duty cycles, dropping them if they cannot be read.
+///
+/// Not every interface reports duty cycles, so a failure does not mark the CCU as down.
+async fn poll_duty_cycles(client: &HomeMaticClient, metrics: &SharedMetrics) {
+    let mut buf = String::new();
+    let duty_cycles = match client.duty_cycles(&mut buf).await {
+        Ok(duty_cycles) => duty_cycles
+            .into_iter()
+            .map(|interface| DutyCycleGauge {
+                address: interface.address,
+                description: interface.description,
+                connected: interface.connected,
+                level: interface.level,
+            })
+            .collect(),
+        Err(err) => {
+            warn!("Failed to read duty cycles: {}", err);
+            vec![]
+        }
+    };
+
+    metrics.lock().unwrap().duty_cycles = duty_cycles;
+}
+
+/// Reload the channel list and all values.
+async fn reload(
+    client: &HomeMaticClient,
+    metrics: &SharedMetrics,
+    batch: usize,
+) -> Result<(), Box<dyn Error + 'static>> {
+    let channels = load_channels(client).await?;
+    let addresses: Vec<String> = channels.keys().cloned().collect();
+    let values = poll_values(client, &addresses, batch).await?;
+
+    let mut metrics = metrics.lock().unwrap();
+    metrics.channels = channels;
+    metrics.values = values;
+    Ok(())
+}
+
+/// Let the CCU send a PONG event to check that events still arrive.
+async fn ping(client: &HomeMaticClient) -> Result<(), Box<dyn Error + 'static>> {
+    let mut buf = String::new();
+    client.ping("homematic-exporter", &mut buf).await?;
+    Ok(())
+}
+
+async fn next_event(subscription: &mut Option<Subscription<'_>>) -> Option<Event> {
+    match subscription {
+        Some(subscription) => subscription.next().await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Wait for Ctrl-C or, on Unix, SIGTERM.
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        match signal(SignalKind::terminate()) {
+            Ok(mut terminate) => {
+                tokio::select! {
+                    _ = tokio::signal::ctrl_c() => {}
+                    _ = terminate.recv() => {}
+                }
+                return;
+            }
+            Err(err) => warn!("Failed to handle SIGTERM: {}", err),
+        }
+    }
+    tokio::signal::ctrl_c().await.ok();
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> Result<(), Box<dyn Error + 'static>> {
+    let cli = Cli::parse();
+
+    stderrlog::new()
+        // warnings by default, -v for info, -vv for debug
+        .verbosity(1 + cli.verbose as usize)
+        .init()?;
+
+    let client = cli.connection.connect()?;
+    let metrics = SharedMetrics::default();
+
+    let server = serve_metrics(cli.listen, metrics.clone());
+    tokio::spawn(async move {
+        if let Err(err) = server.await {
+            warn!("Metrics server failed: {}", err);
+        }
+    });
+    info!("Serving metrics on http://{}/metrics", cli.listen);
+
+    let callback = cli.callback_host.as_ref().map(|host| CallbackArgs {
+        listen: cli.callback_listen,
+        callback_host: host.clone(),
+    });
+    let mut events = match &callback {
+        Some(args) => Some(Subscription::start(&client, args).await?),
+        None => None,
+    };
+    // every tick pings the CCU, which answers with a PONG event to all subscribers
+    let event_timeout = Duration::from_secs(cli.interval.max(1) * 3);
+    let mut last_event = Instant::now();
+
+    let shutdown = shutdown_signal();
+    tokio::pin!(shutdown);
+
+    let mut interval = tokio::time::interval(Duration::from_secs(cli.interval.max(1)));
+    let mut stale = true;
+    loop {
+        tokio::select! {
+            _ = &mut shutdown => break,
+            _ = interval.tick() => {
+                if let Some(args) = &callback {
+                    if events.is_none() || last_event.elapsed() > event_timeout {
+                        // e.g. the CCU restarted and forgot the subscription
+                        if let Some(subscription) = events.take() {
+                            warn!(
+                                "No events for {} seconds, subscribing again",
+                                last_event.elapsed().as_secs()
+                            );
+                            if let Err(err) = subscription.stop().await {
+                                warn!("Failed to unsubscribe: {}", err);
+                            }
+                        }
+                        match Subscription::start(&client, args).await {
+                            Ok(subscription) => events = Some(subscription),
+                            Err(err) => warn!("Failed to subscribe to events: {}", err),
+                        }
+                        last_event = Instant::now();
+                        // events may have been missed
+                        stale = true;
+                    }
+                }
+
+                // without events, values are only current after polling them
+                let result = if stale || events.is_none() {
+                    reload(&client, &metrics, cli.batch).await
+                } else {
+                    ping(&client).await
+                };
+                let result = match result {
+                    Ok(()) => poll_service_messages(&client, &metrics).await,
+                    Err(err) => Err(err),
+                };
+                let up = match result {
+                    Ok(()) => {
+                        stale = false;
+                        true
+                    }
+                    Err(err) => {
+                        warn!("Polling failed: {}", err);
+                        false
+                    }
+                };
+                metrics.lock().unwrap().up = up;
+                if up {
+                    poll_duty_cycles(&client, &metrics).await;
+                }
+            }
+            event = next_event(&mut events) => {
+                last_event = Instant::now();
+                match event {
+                    Some(Event::Value(event)) => {
+                        // only readable parameters that were polled, events of ACTION
+                        // parameters like PRESS_SHORT would otherwise be kept forever
+                        let mut metrics = metrics.lock().unwrap();
+                        if let (Some(current), Some(value)) = (
+                            metrics.values.get_mut(&(event.address, event.value_key)),
+                            numeric_value(&event.value),
+                        ) {
+                            *current = value;
+                        }
+                    }
+                    Some(_) => {
+                        // devices were added, removed or changed
+                        stale = true;
+                    }
+                    None => {
+                        // the callback server stopped, subscribe again on the next tick
+                        events = None;
+                    }
+                }
+            }
+        }
+    }
+
+    if let Some(subscription) = events {
+        subscription.stop().await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use super::*;
+
+    #[test]
+    fn escape_label() {
+        assert_eq!(escape("LEQ0123456:1"), "LEQ0123456:1");
+        assert_eq!(escape(r#"a "b" \c"#), r#"a \"b\" \\c"#);
+        assert_eq!(escape("a\nb"), "a\\nb");
+    }
+
+    #[test]
+    fn numeric() {
+        assert_eq!(numeric_value(&Value::Double(21.5)), Some(21.5));
+        assert_eq!(numeric_value(&Value::Int(-3)), Some(-3.0));
+        assert_eq!(numeric_value(&Value::Bool(true)), Some(1.0));
+        assert_eq!(numeric_value(&Value::Bool(false)), Some(0.0));
+        assert_eq!(numeric_value(&Value::String(Cow::Borrowed("1"))), None);
+    }
+
+    #[test]
+    fn render() {
+        let mut metrics = Metrics {
+            up: true,
+            ..Metrics::default()
+        };
+        metrics.channels.insert(
+            "LEQ0123456:1".to_string(),
+            ChannelLabels {
+                device_type: "HM-CC-RT-DN".to_string(),
+                channel_type: "WEATHER".to_string(),
+            },
+        );
+        metrics.values.insert(
+            ("LEQ0123456:1".to_string(), "TEMPERATURE".to_string()),
+            21.5,
+        );
+        metrics
+            .values
+            .insert(("OEQ0000000:1".to_string(), "STATE".to_string()), 1.0);
+        metrics.service_messages.insert("LOWBAT".to_string(), 2);
+        metrics.duty_cycles = vec![
+            DutyCycleGauge {
+                address: "NEQ0000000".to_string(),
+                description: "CCU \"2\"".to_string(),
+                connected: true,
+                level: Some(3.0),
+            },
+            DutyCycleGauge {
+                address: "NEQ0000001".to_string(),
+                description: String::new(),
+                connected: false,
+                level: None,
+            },
+        ];
+
+        let rendered = metrics.render();
+        let samples: Vec<&str> = rendered
+            .lines()
+            .filter(|line| !line.starts_with('#'))
+            .collect();
+        assert_eq!(
+            samples,
+            vec![
+                "homematic_up 1",
+                r#"homematic_value{address="LEQ0123456:1",device_type="HM-CC-RT-DN",channel_type="WEATHER",parameter="TEMPERATURE"} 21.5"#,
+                r#"homematic_value{address="OEQ0000000:1",device_type="",channel_type="",parameter="STATE"} 1"#,
+                r#"homematic_service_messages{type="LOWBAT"} 2"#,
+                r#"homematic_duty_cycle_percent{address="NEQ0000000",description="CCU \"2\""} 3"#,
+                r#"homematic_interface_connected{address="NEQ0000000",description="CCU \"2\""} 1"#,
+                r#"homematic_interface_connected{address="NEQ0000001",description=""} 0"#,
+            ]
+        );
+        assert!(rendered.contains("# TYPE homematic_value gauge\n"));
+    }
+}
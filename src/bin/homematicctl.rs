@@ -1,7 +1,7 @@
 use std::collections::HashMap;
-use std::env;
 use std::error::Error;
 use std::fmt::Debug;
+use std::io::{BufRead, Write};
 
 use clap::{Args, Parser, Subcommand, ValueEnum};
 use comfy_table::Table;
@@ -10,15 +10,45 @@ use log::warn;
 use serde::{Deserialize, Serialize};
 use stderrlog::LogLevelNum;
 
+use crate::apply::ApplyCommand;
+use crate::backup::create::BackupCommand;
+use crate::backup::restore::RestoreCommand;
+use crate::battery::BatteryCommand;
+use crate::config::ConnectionArgs;
+use crate::firmware::FirmwareCommand;
+use crate::interface::InterfaceCommand;
 use crate::message::MessageCommand;
+use crate::pair::PairCommand;
 use crate::param::ParamCommand;
+use crate::radio::RadioCommand;
+use crate::schedule::ScheduleCommand;
+use crate::security::SecurityCommand;
+use crate::status::StatusCommand;
+use crate::team::TeamCommand;
+use crate::value::ValueCommand;
+use crate::watch::WatchCommand;
 use device::inspect::InspectDeviceCommand;
 use device::list::ListDevicesCommand;
 use homematic_rs::{DeviceDescription, HomeMaticClient};
 
+mod apply;
+mod backup;
+mod battery;
+mod config;
 mod device;
+mod events;
+mod firmware;
+mod interface;
 mod message;
+mod pair;
 mod param;
+mod radio;
+mod schedule;
+mod security;
+mod status;
+mod team;
+mod value;
+mod watch;
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
@@ -34,6 +64,9 @@ struct Cli {
     /// output format
     #[arg(short, long, default_value = "table")]
     output_format: Format,
+
+    #[command(flatten)]
+    connection: ConnectionArgs,
 }
 
 #[derive(ValueEnum, Copy, Clone, Eq, PartialEq, Debug)]
@@ -42,21 +75,83 @@ pub enum Format {
     Json,
 }
 
+/// Ask a yes/no question on the terminal.
+pub fn confirm(question: &str) -> Result<bool, Box<dyn Error + 'static>> {
+    print!("{} [y/N] ", question);
+    std::io::stdout().flush()?;
+
+    let mut answer = String::new();
+    std::io::stdin().lock().read_line(&mut answer)?;
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
+}
+
 #[derive(Subcommand)]
 enum Namespace {
+    /// Bring MASTER paramsets and links to the state described in a file
+    Apply(ApplyCommand),
+    /// Write MASTER and link paramsets of all devices to an archive
+    Backup(BackupCommand),
+    /// Battery state of battery-powered devices, most urgent first
+    Battery(BatteryCommand),
     /// Device related commands
     Device {
         #[command(subcommand)]
         command: DeviceCommand,
     },
+    /// Firmware updates
+    Firmware {
+        #[command(subcommand)]
+        command: FirmwareCommand,
+    },
+    /// BidCos-RF interfaces
+    Interface {
+        #[command(subcommand)]
+        command: InterfaceCommand,
+    },
     Message {
         #[command(subcommand)]
         command: MessageCommand,
     },
+    /// Pair new devices
+    Pair {
+        #[command(subcommand)]
+        command: PairCommand,
+    },
     Param {
         #[command(subcommand)]
         command: ParamCommand,
     },
+    /// Re-apply MASTER and link paramsets from an archive
+    Restore(RestoreCommand),
+    /// Radio reception quality
+    Radio {
+        #[command(subcommand)]
+        command: RadioCommand,
+    },
+    /// Week programs of heating thermostats
+    Schedule {
+        #[command(subcommand)]
+        command: ScheduleCommand,
+    },
+    /// AES keys of secured transmission
+    Security {
+        #[command(subcommand)]
+        command: SecurityCommand,
+    },
+    /// Show reachability, version and capabilities of the interface
+    Status(StatusCommand),
+    /// Teams of channels (e.g. smoke detector groups)
+    Team {
+        #[command(subcommand)]
+        command: TeamCommand,
+    },
+    /// Read and write values of the VALUES paramset
+    Value {
+        #[command(subcommand)]
+        command: ValueCommand,
+    },
+    /// Print value changes reported by the CCU until interrupted
+    Watch(WatchCommand),
 }
 
 #[tokio::main(flavor = "current_thread")]
@@ -67,29 +162,70 @@ async fn main() -> Result<(), Box<dyn Error + 'static>> {
         .show_module_names(true)
         .verbosity(LogLevelNum::Debug) //cli.verbose as usize)
         .init()?;
-    println!("{}", cli.verbose);
 
-    let xmlrpc = xrs_xmlrpc::client::reqwest::XmlRpcClientBuilder::new(
-        env::var("HM_URL").expect("HM_URL env var"),
-    )
-    .basic_auth(
-        env::var("HM_USERNAME").expect("HM_USERNAME env var"),
-        Some(env::var("HM_PASSWORD").expect("HM_PASSWORD env var")),
-    )
-    .build()?;
-    let client = HomeMaticClient::new(xmlrpc);
+    let client = cli.connection.connect()?;
 
     return match cli.namespace {
+        Namespace::Apply(cmd) => cmd.exec(&client, cli.output_format).await,
+        Namespace::Backup(cmd) => cmd.exec(&client, cli.output_format).await,
+        Namespace::Battery(cmd) => cmd.exec(&client, cli.output_format).await,
         Namespace::Device { command } => match command {
             DeviceCommand::List(cmd) => cmd.exec(&client, cli.output_format).await,
             DeviceCommand::Inspect(cmd) => cmd.exec(&client, cli.output_format).await,
+            DeviceCommand::Delete(cmd) => cmd.exec(&client, cli.output_format).await,
+            DeviceCommand::Replace(cmd) => cmd.exec(&client, cli.output_format).await,
+            DeviceCommand::Sync(cmd) => cmd.exec(&client, cli.output_format).await,
+            DeviceCommand::Health(cmd) => cmd.exec(&client, cli.output_format).await,
+        },
+        Namespace::Firmware { command } => match command {
+            FirmwareCommand::List(cmd) => cmd.exec(&client, cli.output_format).await,
+            FirmwareCommand::Update(cmd) => cmd.exec(&client, cli.output_format).await,
+        },
+        Namespace::Interface { command } => match command {
+            InterfaceCommand::List(cmd) => cmd.exec(&client, cli.output_format).await,
+            InterfaceCommand::Assign(cmd) => cmd.exec(&client, cli.output_format).await,
+            InterfaceCommand::Led(cmd) => cmd.exec(&client, cli.output_format).await,
+            InterfaceCommand::Health(cmd) => cmd.exec(&client, cli.output_format).await,
         },
         Namespace::Message { command } => match command {
             MessageCommand::List(cmd) => cmd.exec(&client, cli.output_format).await,
         },
+        Namespace::Pair { command } => match command {
+            PairCommand::Start(cmd) => cmd.exec(&client, cli.output_format).await,
+            PairCommand::Status(cmd) => cmd.exec(&client, cli.output_format).await,
+            PairCommand::Wait(cmd) => cmd.exec(&client, cli.output_format).await,
+        },
         Namespace::Param { command } => match command {
             ParamCommand::List(cmd) => cmd.exec(&client, cli.output_format).await,
             ParamCommand::Get(cmd) => cmd.exec(&client, cli.output_format).await,
+            ParamCommand::Diff(cmd) => cmd.exec(&client, cli.output_format).await,
+            ParamCommand::ApplyTemplate(cmd) => cmd.exec(&client, cli.output_format).await,
+        },
+        Namespace::Restore(cmd) => cmd.exec(&client, cli.output_format).await,
+        Namespace::Radio { command } => match command {
+            RadioCommand::Report(cmd) => cmd.exec(&client, cli.output_format).await,
+        },
+        Namespace::Schedule { command } => match command {
+            ScheduleCommand::Get(cmd) => cmd.exec(&client, cli.output_format).await,
+            ScheduleCommand::Set(cmd) => cmd.exec(&client, cli.output_format).await,
+        },
+        Namespace::Security { command } => match command {
+            SecurityCommand::List(cmd) => cmd.exec(&client, cli.output_format).await,
+            SecurityCommand::Mismatch(cmd) => cmd.exec(&client, cli.output_format).await,
+            SecurityCommand::Rekey(cmd) => cmd.exec(&client, cli.output_format).await,
+            SecurityCommand::ChangeKey(cmd) => cmd.exec(&client, cli.output_format).await,
+        },
+        Namespace::Status(cmd) => cmd.exec(&client, cli.output_format).await,
+        Namespace::Team { command } => match command {
+            TeamCommand::List(cmd) => cmd.exec(&client, cli.output_format).await,
+            TeamCommand::Assign(cmd) => cmd.exec(&client, cli.output_format).await,
+            TeamCommand::Create(cmd) => cmd.exec(&client, cli.output_format).await,
+        },
+        Namespace::Value { command } => match command {
+            ValueCommand::Get(cmd) => cmd.exec(&client, cli.output_format).await,
+            ValueCommand::Set(cmd) => cmd.exec(&client, cli.output_format).await,
+            ValueCommand::Press(cmd) => cmd.exec(&client, cli.output_format).await,
         },
+        Namespace::Watch(cmd) => cmd.exec(&client, cli.output_format).await,
     };
 }
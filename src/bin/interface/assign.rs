@@ -0,0 +1,40 @@
+use crate::Format;
+use clap::Args;
+use homematic_rs::HomeMaticClient;
+use std::error::Error;
+
+#[derive(Args)]
+pub struct AssignInterfaceCommand {
+    /// Device address
+    device: String,
+
+    /// Interface address
+    interface: String,
+
+    /// Adapt the assignment automatically to the reception conditions
+    #[arg(long)]
+    roaming: bool,
+}
+
+impl AssignInterfaceCommand {
+    pub async fn exec(
+        &self,
+        client: &HomeMaticClient,
+        _format: Format,
+    ) -> Result<(), Box<dyn Error + 'static>> {
+        let mut buf = String::new();
+        let interfaces = client.list_bidcos_interfaces(&mut buf).await?;
+        if !interfaces
+            .iter()
+            .any(|interface| interface.address == self.interface)
+        {
+            return Err(format!("unknown interface: {}", self.interface).into());
+        }
+
+        client
+            .set_bidcos_interface(&self.device, &self.interface, self.roaming, &mut buf)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,164 @@
+use crate::Format;
+use clap::Args;
+use comfy_table::Table;
+use homematic_rs::{DutyCycle, HomeMaticClient};
+use log::warn;
+use serde::Serialize;
+use std::error::Error;
+use std::time::Duration;
+
+#[derive(Args)]
+pub struct InterfaceHealthCommand {
+    /// Duty cycle in percent above which a warning is reported
+    #[arg(long, default_value_t = 60.0)]
+    warn: f64,
+
+    /// Duty cycle in percent above which the interface is critical
+    #[arg(long, default_value_t = 90.0)]
+    critical: f64,
+
+    /// Check continuously with this interval in seconds
+    #[arg(long)]
+    interval: Option<u64>,
+}
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "camelCase")]
+enum Health {
+    Ok,
+    /// The duty cycle could not be read.
+    Unknown,
+    Warning,
+    Critical,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct OutputInterfaceHealth {
+    address: String,
+    description: String,
+    connected: bool,
+    duty_cycle: Option<f64>,
+    health: Health,
+}
+
+impl InterfaceHealthCommand {
+    pub async fn exec(
+        &self,
+        client: &HomeMaticClient,
+        format: Format,
+    ) -> Result<(), Box<dyn Error + 'static>> {
+        let mut buf = String::new();
+
+        let interval = match self.interval {
+            Some(interval) => Duration::from_secs(interval),
+            None => {
+                let health = self.check(client.duty_cycles(&mut buf).await?);
+                print_health(&health, format)?;
+                return match health.iter().map(|interface| interface.health).max() {
+                    Some(Health::Critical) => Err("duty cycle limit reached".into()),
+                    _ => Ok(()),
+                };
+            }
+        };
+
+        loop {
+            match client.duty_cycles(&mut buf).await {
+                Ok(duty_cycles) => {
+                    for interface in self.check(duty_cycles) {
+                        if interface.health != Health::Ok {
+                            warn!(
+                                "{} ({}): duty cycle {}{}",
+                                interface.address,
+                                interface.description,
+                                interface
+                                    .duty_cycle
+                                    .map(|dc| format!("{}%", dc))
+                                    .unwrap_or_else(|| "unknown".to_string()),
+                                if interface.connected {
+                                    ""
+                                } else {
+                                    ", not connected"
+                                }
+                            );
+                        }
+                        if format == Format::Json {
+                            println!("{}", serde_json::to_string(&interface)?);
+                        }
+                    }
+                }
+                Err(err) => warn!("Failed to read duty cycle: {}", err),
+            }
+
+            tokio::select! {
+                _ = tokio::signal::ctrl_c() => return Ok(()),
+                _ = tokio::time::sleep(interval) => {}
+            }
+        }
+    }
+
+    fn check(&self, duty_cycles: Vec<DutyCycle>) -> Vec<OutputInterfaceHealth> {
+        duty_cycles
+            .into_iter()
+            .map(|dc| {
+                let health = match dc.level {
+                    _ if !dc.connected => Health::Critical,
+                    Some(level) if level >= self.critical => Health::Critical,
+                    Some(level) if level >= self.warn => Health::Warning,
+                    Some(_) => Health::Ok,
+                    None => Health::Unknown,
+                };
+                OutputInterfaceHealth {
+                    address: dc.address,
+                    description: dc.description,
+                    connected: dc.connected,
+                    duty_cycle: dc.level,
+                    health,
+                }
+            })
+            .collect()
+    }
+}
+
+fn print_health(
+    health: &[OutputInterfaceHealth],
+    format: Format,
+) -> Result<(), Box<dyn Error + 'static>> {
+    match format {
+        Format::Table => {
+            let mut table = Table::new();
+            table
+                .set_header(vec![
+                    "ADDRESS",
+                    "DESCRIPTION",
+                    "CONNECTED",
+                    "DUTY CYCLE",
+                    "HEALTH",
+                ])
+                .load_preset(comfy_table::presets::NOTHING);
+
+            for interface in health {
+                table.add_row(vec![
+                    interface.address.clone(),
+                    interface.description.clone(),
+                    interface.connected.to_string(),
+                    interface
+                        .duty_cycle
+                        .map(|dc| format!("{}%", dc))
+                        .unwrap_or_default(),
+                    match interface.health {
+                        Health::Ok => "ok",
+                        Health::Unknown => "unknown",
+                        Health::Warning => "warning",
+                        Health::Critical => "critical",
+                    }
+                    .to_string(),
+                ]);
+            }
+
+            println!("{table}");
+        }
+        Format::Json => serde_json::to_writer_pretty(std::io::stdout(), health)?,
+    }
+    Ok(())
+}
@@ -0,0 +1,28 @@
+use crate::Format;
+use clap::Args;
+use homematic_rs::HomeMaticClient;
+use std::error::Error;
+
+#[derive(Args)]
+pub struct InterfaceLedCommand {
+    /// Interface address of the LAN gateway
+    interface: String,
+
+    /// Switch the LED off
+    #[arg(long)]
+    off: bool,
+}
+
+impl InterfaceLedCommand {
+    pub async fn exec(
+        &self,
+        client: &HomeMaticClient,
+        _format: Format,
+    ) -> Result<(), Box<dyn Error + 'static>> {
+        let mut buf = String::new();
+        client
+            .set_rflgw_info_led(&self.interface, !self.off, &mut buf)
+            .await?;
+        Ok(())
+    }
+}
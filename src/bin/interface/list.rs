@@ -0,0 +1,121 @@
+use crate::Format;
+use clap::Args;
+use comfy_table::Table;
+use homematic_rs::HomeMaticClient;
+use serde::Serialize;
+use std::error::Error;
+
+#[derive(Args)]
+pub struct ListInterfacesCommand {
+    /// Show the interface assignment of every device
+    #[arg(long)]
+    devices: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct OutputInterface {
+    address: String,
+    description: String,
+    connected: bool,
+    default: bool,
+    duty_cycle: Option<i32>,
+    devices: Vec<OutputAssignment>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct OutputAssignment {
+    #[serde(rename = "type")]
+    ty: String,
+    address: String,
+    roaming: bool,
+}
+
+impl ListInterfacesCommand {
+    pub async fn exec(
+        &self,
+        client: &HomeMaticClient,
+        format: Format,
+    ) -> Result<(), Box<dyn Error + 'static>> {
+        let mut buf = String::new();
+        let mut interfaces: Vec<OutputInterface> = client
+            .list_bidcos_interfaces(&mut buf)
+            .await?
+            .into_iter()
+            .map(|interface| OutputInterface {
+                address: interface.address,
+                description: interface.description,
+                connected: interface.connected,
+                default: interface.default,
+                duty_cycle: interface.duty_cycle,
+                devices: vec![],
+            })
+            .collect();
+
+        for dev in client.list_devices(&mut buf).await? {
+            if !dev.parent.is_empty() {
+                continue;
+            }
+            let interface = dev.interface.as_ref().and_then(|address| {
+                interfaces
+                    .iter_mut()
+                    .find(|interface| &interface.address == address)
+            });
+            if let Some(interface) = interface {
+                interface.devices.push(OutputAssignment {
+                    ty: dev.ty,
+                    address: dev.address,
+                    roaming: dev.roaming.unwrap_or_default(),
+                });
+            }
+        }
+
+        match format {
+            Format::Table => {
+                let mut table = Table::new();
+                table
+                    .set_header(vec![
+                        "ADDRESS",
+                        "DESCRIPTION",
+                        "CONNECTED",
+                        "DEFAULT",
+                        "DUTY CYCLE",
+                        "DEVICES",
+                    ])
+                    .load_preset(comfy_table::presets::NOTHING);
+
+                for interface in &interfaces {
+                    table.add_row(vec![
+                        interface.address.clone(),
+                        interface.description.clone(),
+                        interface.connected.to_string(),
+                        interface.default.to_string(),
+                        interface
+                            .duty_cycle
+                            .map(|dc| format!("{}%", dc))
+                            .unwrap_or_default(),
+                        interface.devices.len().to_string(),
+                    ]);
+                    if self.devices {
+                        for dev in &interface.devices {
+                            table.add_row(vec![
+                                format!("    {}", dev.address),
+                                dev.ty.clone(),
+                                String::new(),
+                                String::new(),
+                                String::new(),
+                                if dev.roaming { "roaming" } else { "" }.to_string(),
+                            ]);
+                        }
+                    }
+                }
+
+                println!("{table}");
+            }
+            Format::Json => serde_json::to_writer_pretty(std::io::stdout(), &interfaces)?,
+        }
+
+        Ok(())
+    }
+}
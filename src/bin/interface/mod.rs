@@ -0,0 +1,23 @@
+use clap::Subcommand;
+
+use crate::interface::assign::AssignInterfaceCommand;
+use crate::interface::health::InterfaceHealthCommand;
+use crate::interface::led::InterfaceLedCommand;
+use crate::interface::list::ListInterfacesCommand;
+
+pub mod assign;
+pub mod health;
+pub mod led;
+pub mod list;
+
+#[derive(Subcommand)]
+pub enum InterfaceCommand {
+    /// Lists BidCos-RF interfaces
+    List(ListInterfacesCommand),
+    /// Assign a device to an interface
+    Assign(AssignInterfaceCommand),
+    /// Switch the info LED of a LAN gateway
+    Led(InterfaceLedCommand),
+    /// Check the duty cycle of interfaces and access points
+    Health(InterfaceHealthCommand),
+}
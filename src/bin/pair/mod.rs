@@ -0,0 +1,19 @@
+use clap::Subcommand;
+
+use crate::pair::start::StartPairCommand;
+use crate::pair::status::PairStatusCommand;
+use crate::pair::wait::WaitPairCommand;
+
+pub mod start;
+pub mod status;
+pub mod wait;
+
+#[derive(Subcommand)]
+pub enum PairCommand {
+    /// Enable the install mode
+    Start(StartPairCommand),
+    /// Show the remaining time of the install mode
+    Status(PairStatusCommand),
+    /// Wait for newly paired devices
+    Wait(WaitPairCommand),
+}
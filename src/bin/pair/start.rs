@@ -0,0 +1,48 @@
+use crate::Format;
+use clap::Args;
+use homematic_rs::{HomeMaticClient, InstallMode};
+use std::error::Error;
+use std::time::Duration;
+
+#[derive(Args)]
+pub struct StartPairCommand {
+    /// duration of the install mode in seconds
+    #[arg(long, default_value_t = 60)]
+    timeout: u64,
+
+    /// reset devices to factory state while pairing
+    #[arg(long, conflicts_with = "address")]
+    reset: bool,
+
+    /// only pair the device with this SGTIN (HmIP)
+    #[arg(long)]
+    address: Option<String>,
+}
+
+impl StartPairCommand {
+    pub async fn exec(
+        &self,
+        client: &HomeMaticClient,
+        _format: Format,
+    ) -> Result<(), Box<dyn Error + 'static>> {
+        let mut buf = String::new();
+        let timeout = Duration::from_secs(self.timeout);
+
+        if let Some(address) = &self.address {
+            client
+                .set_install_mode_for_address(true, timeout, address, &mut buf)
+                .await?;
+        } else {
+            let mode = if self.reset {
+                InstallMode::Reset
+            } else {
+                InstallMode::Normal
+            };
+            client
+                .set_install_mode_with_timeout(true, timeout, mode, &mut buf)
+                .await?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,61 @@
+use crate::Format;
+use clap::Args;
+use homematic_rs::HomeMaticClient;
+use serde::Serialize;
+use std::error::Error;
+use std::io::Write;
+use std::time::Duration;
+
+#[derive(Args)]
+pub struct PairStatusCommand {
+    /// count down until the install mode ends
+    #[arg(long)]
+    follow: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct OutputInstallMode {
+    active: bool,
+    remaining_secs: u64,
+}
+
+impl PairStatusCommand {
+    pub async fn exec(
+        &self,
+        client: &HomeMaticClient,
+        format: Format,
+    ) -> Result<(), Box<dyn Error + 'static>> {
+        let mut buf = String::new();
+
+        loop {
+            let remaining = client.get_install_mode(&mut buf).await?.as_secs();
+            match format {
+                Format::Table if self.follow => {
+                    print!("\rinstall mode: {:>4}s remaining", remaining);
+                    std::io::stdout().flush()?;
+                }
+                Format::Table if remaining == 0 => println!("install mode: off"),
+                Format::Table => println!("install mode: {}s remaining", remaining),
+                Format::Json => println!(
+                    "{}",
+                    serde_json::to_string(&OutputInstallMode {
+                        active: remaining > 0,
+                        remaining_secs: remaining,
+                    })?
+                ),
+            }
+
+            if !self.follow || remaining == 0 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_secs(1)).await;
+        }
+
+        if self.follow && format == Format::Table {
+            println!();
+        }
+
+        Ok(())
+    }
+}
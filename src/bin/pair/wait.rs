@@ -0,0 +1,88 @@
+use crate::events::{CallbackArgs, Subscription};
+use crate::Format;
+use clap::Args;
+use homematic_rs::{Event, HomeMaticClient};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::error::Error;
+use std::time::Duration;
+
+#[derive(Args)]
+pub struct WaitPairCommand {
+    /// maximum time to wait in seconds
+    #[arg(long, default_value_t = 60)]
+    timeout: u64,
+
+    /// stop after this number of new devices
+    #[arg(long)]
+    count: Option<usize>,
+
+    #[command(flatten)]
+    callback: CallbackArgs,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct OutputNewDevice {
+    #[serde(rename = "type")]
+    ty: String,
+    address: String,
+    firmware: Option<String>,
+}
+
+impl WaitPairCommand {
+    pub async fn exec(
+        &self,
+        client: &HomeMaticClient,
+        format: Format,
+    ) -> Result<(), Box<dyn Error + 'static>> {
+        let mut buf = String::new();
+        let mut known: HashSet<String> = client
+            .list_devices(&mut buf)
+            .await?
+            .into_iter()
+            .map(|dev| dev.address)
+            .collect();
+
+        let mut subscription = Subscription::start(client, &self.callback).await?;
+        let deadline = tokio::time::sleep(Duration::from_secs(self.timeout));
+        tokio::pin!(deadline);
+
+        let mut new_devices = vec![];
+        while self.count.is_none_or(|count| new_devices.len() < count) {
+            let event = tokio::select! {
+                _ = &mut deadline => break,
+                _ = tokio::signal::ctrl_c() => break,
+                event = subscription.next() => match event {
+                    Some(event) => event,
+                    None => break,
+                },
+            };
+
+            // The CCU announces all devices unknown to us after registration, so only report
+            // devices missing from the initial device list.
+            if let Event::NewDevices { devices, .. } = event {
+                for dev in devices {
+                    if dev.parent.is_empty() && known.insert(dev.address.clone()) {
+                        if format == Format::Table {
+                            println!("paired {} {}", dev.ty, dev.address);
+                        }
+                        new_devices.push(OutputNewDevice {
+                            ty: dev.ty,
+                            address: dev.address,
+                            firmware: dev.firmware,
+                        });
+                    }
+                }
+            }
+        }
+
+        subscription.stop().await?;
+
+        if format == Format::Json {
+            serde_json::to_writer_pretty(std::io::stdout(), &new_devices)?;
+        }
+
+        Ok(())
+    }
+}
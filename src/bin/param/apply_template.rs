@@ -0,0 +1,197 @@
+use crate::value::coerce_value;
+use crate::Format;
+use clap::Args;
+use comfy_table::Table;
+use futures::stream::{self, StreamExt};
+use homematic_rs::{DeviceDescription, HomeMaticClient, Paramset};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+
+#[derive(Args)]
+pub struct ApplyTemplateCommand {
+    /// device or channel type
+    #[arg(long = "type")]
+    ty: String,
+
+    /// only write to this channel index of matching devices
+    #[arg(long)]
+    channel: Option<i32>,
+
+    /// paramset type
+    paramset_type: String,
+
+    /// values to write as KEY=VALUE
+    #[arg(required = true, value_parser = parse_assignment)]
+    values: Vec<(String, String)>,
+
+    /// number of devices written concurrently
+    #[arg(long, default_value_t = 4)]
+    parallel: usize,
+
+    /// only validate the values
+    #[arg(long)]
+    dry_run: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct OutputTemplateResult {
+    address: String,
+    written: Vec<String>,
+    error: Option<String>,
+}
+
+fn parse_assignment(arg: &str) -> Result<(String, String), String> {
+    match arg.split_once('=') {
+        Some((key, value)) if !key.is_empty() => Ok((key.to_string(), value.to_string())),
+        _ => Err(format!("expected KEY=VALUE, got {}", arg)),
+    }
+}
+
+impl ApplyTemplateCommand {
+    pub async fn exec(
+        &self,
+        client: &HomeMaticClient,
+        format: Format,
+    ) -> Result<(), Box<dyn Error + 'static>> {
+        let mut buf = String::new();
+        let targets = self.targets(client.list_devices(&mut buf).await?);
+        if targets.is_empty() {
+            return Err(format!("no devices or channels of type {}", self.ty).into());
+        }
+
+        let mut results: Vec<OutputTemplateResult> = stream::iter(targets)
+            .map(|(device, addresses)| async move {
+                match self.apply(client, &addresses).await {
+                    Ok(written) => OutputTemplateResult {
+                        address: device,
+                        written,
+                        error: None,
+                    },
+                    Err(err) => OutputTemplateResult {
+                        address: device,
+                        written: vec![],
+                        error: Some(err.to_string()),
+                    },
+                }
+            })
+            .buffer_unordered(self.parallel.max(1))
+            .collect()
+            .await;
+        results.sort_by(|a, b| a.address.cmp(&b.address));
+
+        match format {
+            Format::Table => {
+                let mut table = Table::new();
+                table
+                    .set_header(vec!["ADDRESS", "RESULT"])
+                    .load_preset(comfy_table::presets::NOTHING);
+
+                for result in &results {
+                    let state = match &result.error {
+                        Some(err) => format!("failed: {}", err),
+                        None if self.dry_run => format!("valid: {}", result.written.join(", ")),
+                        None => format!("written: {}", result.written.join(", ")),
+                    };
+                    table.add_row(vec![result.address.clone(), state]);
+                }
+
+                println!("{table}");
+            }
+            Format::Json => serde_json::to_writer_pretty(std::io::stdout(), &results)?,
+        }
+
+        let failed = results
+            .iter()
+            .filter(|result| result.error.is_some())
+            .count();
+        if failed > 0 {
+            return Err(format!("{} of {} devices failed", failed, results.len()).into());
+        }
+        Ok(())
+    }
+
+    /// Addresses to write to, grouped by device.
+    fn targets(&self, devices: Vec<DeviceDescription>) -> Vec<(String, Vec<String>)> {
+        let mut targets = HashMap::<String, Vec<String>>::new();
+        for dev in &devices {
+            if !dev.paramsets.contains(&self.paramset_type) {
+                continue;
+            }
+
+            let device = if dev.parent.is_empty() {
+                &dev.address
+            } else {
+                &dev.parent
+            };
+            let matches = if dev.ty == self.ty {
+                true
+            } else if !dev.parent.is_empty() {
+                // channel of a matching device
+                devices
+                    .iter()
+                    .any(|parent| &parent.address == device && parent.ty == self.ty)
+            } else {
+                false
+            };
+            let channel_matches = match self.channel {
+                Some(channel) => !dev.parent.is_empty() && dev.index == Some(channel),
+                None => true,
+            };
+
+            if matches && channel_matches {
+                targets
+                    .entry(device.clone())
+                    .or_default()
+                    .push(dev.address.clone());
+            }
+        }
+        targets.into_iter().collect()
+    }
+
+    /// Write the values to the addresses having the respective parameters.
+    async fn apply(
+        &self,
+        client: &HomeMaticClient,
+        addresses: &[String],
+    ) -> Result<Vec<String>, Box<dyn Error + 'static>> {
+        let mut buf = String::new();
+        let mut paramsets = vec![];
+        let mut written = vec![];
+        let mut assigned = HashSet::new();
+        for address in addresses {
+            let description = client
+                .get_paramset_description(address, &self.paramset_type, &mut buf)
+                .await?;
+
+            let mut paramset = Paramset::new();
+            for (key, raw) in &self.values {
+                if let Some(pd) = description.get(key) {
+                    let value = coerce_value(pd, raw).map_err(|err| format!("{}: {}", key, err))?;
+                    paramset.insert(key.clone(), value);
+                    written.push(format!("{}.{}", address, key));
+                    assigned.insert(key.as_str());
+                }
+            }
+            if !paramset.is_empty() {
+                paramsets.push((address, paramset));
+            }
+        }
+
+        for (key, _) in &self.values {
+            if !assigned.contains(key.as_str()) {
+                return Err(format!("unknown parameter {}", key).into());
+            }
+        }
+
+        if !self.dry_run {
+            for (address, paramset) in paramsets {
+                client
+                    .put_paramset(address, &self.paramset_type, paramset, &mut buf)
+                    .await?;
+            }
+        }
+        Ok(written)
+    }
+}
@@ -0,0 +1,190 @@
+use crate::backup::{
+    archive_paramset, typed_value_from_json, value_from_json, values_equal, Archive,
+    ArchivedParamset,
+};
+use crate::param::description_key;
+use crate::value::format_value;
+use crate::Format;
+use clap::{ArgGroup, Args};
+use comfy_table::Table;
+use homematic_rs::{HomeMaticClient, ParameterDescription, ParamsetDescription};
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::path::PathBuf;
+
+#[derive(Args)]
+#[command(group(ArgGroup::new("other").required(true)))]
+pub struct DiffParamCommand {
+    /// device or channel address
+    address: String,
+    /// paramset type (MASTER, VALUES or the address of a link peer)
+    paramset_type: String,
+
+    /// compare with the same paramset of this address
+    #[arg(long, group = "other")]
+    with: Option<String>,
+
+    /// compare with the paramset stored in this backup archive
+    #[arg(long, group = "other")]
+    snapshot: Option<PathBuf>,
+
+    /// compare with the default values of the paramset description
+    #[arg(long, group = "other")]
+    defaults: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct OutputParamDiff {
+    key: String,
+    value: Option<JsonValue>,
+    other: Option<JsonValue>,
+    unit: Option<String>,
+}
+
+impl DiffParamCommand {
+    pub async fn exec(
+        &self,
+        client: &HomeMaticClient,
+        format: Format,
+    ) -> Result<(), Box<dyn Error + 'static>> {
+        let mut buf = String::new();
+        let description = client
+            .get_paramset_description(
+                &self.address,
+                description_key(&self.paramset_type),
+                &mut buf,
+            )
+            .await?;
+        let paramset = archive_paramset(
+            &client
+                .get_paramset(&self.address, &self.paramset_type, &mut buf)
+                .await?,
+        );
+
+        let other = if let Some(address) = &self.with {
+            archive_paramset(
+                &client
+                    .get_paramset(address, &self.paramset_type, &mut buf)
+                    .await?,
+            )
+        } else if let Some(path) = &self.snapshot {
+            self.snapshot_paramset(&Archive::load(path)?)?
+        } else {
+            // clap requires one of the options
+            defaults(&description)
+        };
+
+        let keys: BTreeSet<&String> = paramset.keys().chain(other.keys()).collect();
+        let diffs: Vec<OutputParamDiff> = keys
+            .into_iter()
+            .filter(|key| !values_equal(paramset.get(*key), other.get(*key)))
+            .map(|key| OutputParamDiff {
+                key: key.clone(),
+                value: paramset.get(key).cloned(),
+                other: other.get(key).cloned(),
+                unit: description
+                    .get(key)
+                    .and_then(ParameterDescription::unit)
+                    .map(|unit| unit.to_string()),
+            })
+            .collect();
+
+        match format {
+            Format::Table => {
+                let mut table = Table::new();
+                table
+                    .set_header(vec!["KEY", &self.address, self.other_name()])
+                    .load_preset(comfy_table::presets::NOTHING);
+
+                for diff in &diffs {
+                    let pd = description.get(&diff.key);
+                    table.add_row(vec![
+                        diff.key.clone(),
+                        format_json(pd, diff.value.as_ref()),
+                        format_json(pd, diff.other.as_ref()),
+                    ]);
+                }
+
+                println!("{table}");
+            }
+            Format::Json => serde_json::to_writer_pretty(std::io::stdout(), &diffs)?,
+        }
+
+        Ok(())
+    }
+
+    fn other_name(&self) -> &str {
+        if let Some(address) = &self.with {
+            address
+        } else if self.snapshot.is_some() {
+            "SNAPSHOT"
+        } else {
+            "DEFAULT"
+        }
+    }
+
+    fn snapshot_paramset(
+        &self,
+        archive: &Archive,
+    ) -> Result<ArchivedParamset, Box<dyn Error + 'static>> {
+        let paramset = match self.paramset_type.as_str() {
+            "MASTER" => archive
+                .device(&self.address)
+                .and_then(|dev| dev.master.as_ref()),
+            "VALUES" => return Err("VALUES paramsets are not part of backups".into()),
+            peer => archive.links.iter().find_map(|link| {
+                if link.sender == self.address && link.receiver == peer {
+                    link.sender_paramset.as_ref()
+                } else if link.receiver == self.address && link.sender == peer {
+                    link.receiver_paramset.as_ref()
+                } else {
+                    None
+                }
+            }),
+        };
+
+        paramset.cloned().ok_or_else(|| {
+            format!(
+                "snapshot has no paramset {} of {}",
+                self.paramset_type, self.address
+            )
+            .into()
+        })
+    }
+}
+
+/// Default values of all parameters of a paramset description.
+fn defaults(description: &ParamsetDescription) -> ArchivedParamset {
+    description
+        .iter()
+        .filter_map(|(key, pd)| {
+            let default = match pd {
+                ParameterDescription::Float(pd) => JsonValue::from(pd.default as f64),
+                ParameterDescription::Integer(pd) => JsonValue::from(pd.default),
+                ParameterDescription::Bool(pd) => JsonValue::Bool(pd.default),
+                ParameterDescription::Enum(pd) => JsonValue::from(pd.default),
+                ParameterDescription::String(pd) => JsonValue::from(pd.default.clone()),
+                ParameterDescription::Action(_) => return None,
+            };
+            Some((key.clone(), default))
+        })
+        .collect()
+}
+
+/// Render a value like `param get` does, with unit, enum label or special value id.
+fn format_json(description: Option<&ParameterDescription>, value: Option<&JsonValue>) -> String {
+    match (description, value) {
+        (_, None) => "-".to_string(),
+        (Some(pd), Some(value)) => match typed_value_from_json(pd, value) {
+            Ok(typed) => pd.format_value(&typed),
+            Err(_) => value.to_string(),
+        },
+        (None, Some(value)) => match value_from_json(value) {
+            Some(typed) => format_value(&typed),
+            None => value.to_string(),
+        },
+    }
+}
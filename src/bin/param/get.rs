@@ -1,8 +1,9 @@
-use crate::param::OutputParameterDescription;
+use crate::param::description_key;
+use crate::value::format_value;
 use crate::Format;
 use clap::Args;
+use comfy_table::Table;
 use homematic_rs::{HomeMaticClient, Paramset};
-use std::collections::HashMap;
 use std::error::Error;
 
 #[derive(Args)]
@@ -24,7 +25,35 @@ impl GetParamCommand {
             .get_paramset(&self.address, &self.paramset_type, &mut buf)
             .await?;
 
-        serde_json::to_writer_pretty(std::io::stdout(), &paramset)?;
+        match format {
+            Format::Table => {
+                let mut buf = String::new();
+                let description = client
+                    .get_paramset_description(
+                        &self.address,
+                        description_key(&self.paramset_type),
+                        &mut buf,
+                    )
+                    .await?;
+
+                let mut keys: Vec<&String> = paramset.keys().collect();
+                keys.sort();
+
+                let mut table = Table::new();
+                table.load_preset(comfy_table::presets::NOTHING);
+                table.set_header(vec!["KEY", "VALUE"]);
+                for key in keys {
+                    let value = &paramset[key];
+                    let rendered = match description.get(key) {
+                        Some(pd) => pd.format_value(value),
+                        None => format_value(value),
+                    };
+                    table.add_row(vec![key.as_str(), rendered.as_str()]);
+                }
+                println!("{table}");
+            }
+            Format::Json => serde_json::to_writer_pretty(std::io::stdout(), &paramset)?,
+        }
 
         Ok(())
     }
@@ -1,3 +1,5 @@
+use crate::param::apply_template::ApplyTemplateCommand;
+use crate::param::diff::DiffParamCommand;
 use crate::param::get::GetParamCommand;
 use crate::param::list::ListParamsCommand;
 use clap::Subcommand;
@@ -8,9 +10,21 @@ use homematic_rs::{
 };
 use serde::Serialize;
 
+mod apply_template;
+mod diff;
 mod get;
 mod list;
 
+/// Paramset type of the description of a paramset.
+///
+/// Link paramsets are requested by peer address but described as "LINK".
+pub fn description_key(paramset_type: &str) -> &str {
+    match paramset_type {
+        "MASTER" | "VALUES" => paramset_type,
+        _ => "LINK",
+    }
+}
+
 #[derive(Serialize)]
 #[serde(tag = "type", rename_all = "camelCase")]
 pub enum OutputParameterDescription {
@@ -172,9 +186,9 @@ pub struct OutputEnumParameterDescription {
     pub operations: u8,
     // Bitfield: 0x01 : Visible-Flag., 0x02 : Internal-Flag, 0x04 : Transform-Flag, 0x08 : Service-Flag, 0x10 : Sticky-Flag
     pub flags: u8,
-    pub default: String,
-    pub min: String,
-    pub max: String,
+    pub default: i32,
+    pub min: i32,
+    pub max: i32,
     pub unit: Option<String>,
     pub tab_order: Option<i32>,
     pub control: Option<String>,
@@ -232,4 +246,8 @@ pub enum ParamCommand {
     /// List parameter descriptions
     List(ListParamsCommand),
     Get(GetParamCommand),
+    /// Show differing parameters of two paramsets
+    Diff(DiffParamCommand),
+    /// Write the same values to all devices of a type
+    ApplyTemplate(ApplyTemplateCommand),
 }
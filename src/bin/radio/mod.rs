@@ -0,0 +1,11 @@
+use clap::Subcommand;
+
+use crate::radio::report::RadioReportCommand;
+
+pub mod report;
+
+#[derive(Subcommand)]
+pub enum RadioCommand {
+    /// Report weak and one-way radio links
+    Report(RadioReportCommand),
+}
@@ -0,0 +1,160 @@
+use crate::Format;
+use clap::Args;
+use comfy_table::Table;
+use homematic_rs::{HomeMaticClient, RssiPair};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+
+#[derive(Args)]
+pub struct RadioReportCommand {
+    /// Reception strength in dBm below which a link is weak
+    #[arg(long, default_value_t = -85, allow_hyphen_values = true)]
+    weak: i32,
+
+    /// Improvement in dB required to suggest another interface
+    #[arg(long, default_value_t = 6)]
+    margin: i32,
+
+    /// Show all devices, not only those with issues
+    #[arg(long)]
+    all: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct OutputRadioLink {
+    #[serde(rename = "type")]
+    ty: String,
+    address: String,
+    interface: Option<String>,
+    roaming: bool,
+    /// Reception strength of the interface at the device
+    device_rssi: Option<i32>,
+    /// Reception strength of the device at the interface
+    interface_rssi: Option<i32>,
+    issues: Vec<String>,
+    suggested_interface: Option<String>,
+}
+
+/// Weakest direction of a link, if both directions are known.
+fn link_quality(pair: &RssiPair) -> Option<i32> {
+    Some(pair.device_rssi()?.min(pair.peer_rssi()?))
+}
+
+impl RadioReportCommand {
+    pub async fn exec(
+        &self,
+        client: &HomeMaticClient,
+        format: Format,
+    ) -> Result<(), Box<dyn Error + 'static>> {
+        let mut buf = String::new();
+        let interfaces: HashSet<String> = client
+            .list_bidcos_interfaces(&mut buf)
+            .await?
+            .into_iter()
+            .map(|interface| interface.address)
+            .collect();
+        let rssi = client.rssi_info(&mut buf).await?;
+        let empty = HashMap::new();
+
+        let mut links = vec![];
+        for dev in client.list_devices(&mut buf).await? {
+            if !dev.parent.is_empty() || dev.interface.is_none() {
+                continue;
+            }
+
+            let peers = rssi.get(&dev.address).unwrap_or(&empty);
+            let current = dev
+                .interface
+                .as_ref()
+                .and_then(|address| peers.get(address));
+            let roaming = dev.roaming.unwrap_or_default();
+
+            let mut issues = vec![];
+            match current {
+                None => issues.push("no reception data".to_string()),
+                Some(pair) => match (pair.device_rssi(), pair.peer_rssi()) {
+                    (None, None) => issues.push("no reception".to_string()),
+                    (Some(_), None) => issues.push("interface does not receive device".to_string()),
+                    (None, Some(_)) => issues.push("device does not receive interface".to_string()),
+                    (Some(_), Some(_)) => {
+                        if link_quality(pair).unwrap_or_default() < self.weak {
+                            issues.push("weak link".to_string());
+                        }
+                    }
+                },
+            }
+
+            let mut suggested_interface = None;
+            if !roaming {
+                let current_quality = current.and_then(link_quality);
+                let best = peers
+                    .iter()
+                    .filter(|(address, _)| interfaces.contains(*address))
+                    .filter_map(|(address, pair)| Some((address, link_quality(pair)?)))
+                    .max_by_key(|(_, quality)| *quality);
+                if let Some((address, quality)) = best {
+                    let better = match current_quality {
+                        Some(current_quality) => quality >= current_quality + self.margin,
+                        None => true,
+                    };
+                    if better && Some(address) != dev.interface.as_ref() {
+                        suggested_interface = Some(address.clone());
+                    }
+                }
+            }
+
+            if self.all || !issues.is_empty() || suggested_interface.is_some() {
+                links.push(OutputRadioLink {
+                    ty: dev.ty,
+                    address: dev.address,
+                    interface: dev.interface,
+                    roaming,
+                    device_rssi: current.and_then(RssiPair::device_rssi),
+                    interface_rssi: current.and_then(RssiPair::peer_rssi),
+                    issues,
+                    suggested_interface,
+                });
+            }
+        }
+
+        match format {
+            Format::Table => {
+                let mut table = Table::new();
+                table
+                    .set_header(vec![
+                        "TYPE",
+                        "ADDRESS",
+                        "INTERFACE",
+                        "RSSI DEVICE",
+                        "RSSI INTERFACE",
+                        "ISSUES",
+                        "SUGGESTION",
+                    ])
+                    .load_preset(comfy_table::presets::NOTHING);
+
+                for link in &links {
+                    table.add_row(vec![
+                        link.ty.clone(),
+                        link.address.clone(),
+                        link.interface.clone().unwrap_or_default(),
+                        format_rssi(link.device_rssi),
+                        format_rssi(link.interface_rssi),
+                        link.issues.join(", "),
+                        link.suggested_interface.clone().unwrap_or_default(),
+                    ]);
+                }
+
+                println!("{table}");
+            }
+            Format::Json => serde_json::to_writer_pretty(std::io::stdout(), &links)?,
+        }
+
+        Ok(())
+    }
+}
+
+fn format_rssi(rssi: Option<i32>) -> String {
+    rssi.map(|rssi| format!("{} dBm", rssi)).unwrap_or_default()
+}
@@ -0,0 +1,38 @@
+use crate::schedule::ScheduleFile;
+use crate::Format;
+use clap::Args;
+use homematic_rs::{HomeMaticClient, ScheduleFormat, WeekSchedule};
+use std::error::Error;
+
+#[derive(Args)]
+pub struct GetScheduleCommand {
+    /// address of the thermostat
+    address: String,
+
+    /// week program of HmIP thermostats
+    #[arg(long, default_value_t = 1)]
+    program: u8,
+}
+
+impl GetScheduleCommand {
+    pub async fn exec(
+        &self,
+        client: &HomeMaticClient,
+        format: Format,
+    ) -> Result<(), Box<dyn Error + 'static>> {
+        let mut buf = String::new();
+        let paramset = client
+            .get_paramset(&self.address, "MASTER", &mut buf)
+            .await?;
+        let schedule_format = ScheduleFormat::detect(&paramset, self.program)
+            .ok_or_else(|| format!("{} has no week program", self.address))?;
+        let schedule = WeekSchedule::from_paramset(&paramset, schedule_format)?;
+        let file = ScheduleFile::from_schedule(&schedule);
+
+        match format {
+            Format::Table => serde_yaml::to_writer(std::io::stdout(), &file)?,
+            Format::Json => serde_json::to_writer_pretty(std::io::stdout(), &file)?,
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,157 @@
+use std::error::Error;
+
+use clap::Subcommand;
+use homematic_rs::{Slot, WeekSchedule, Weekday, MINUTES_PER_DAY};
+use serde::{Deserialize, Serialize};
+
+use crate::schedule::get::GetScheduleCommand;
+use crate::schedule::set::SetScheduleCommand;
+
+pub mod get;
+pub mod set;
+
+#[derive(Subcommand)]
+pub enum ScheduleCommand {
+    /// Print the week program of a thermostat as YAML
+    Get(GetScheduleCommand),
+    /// Write the week program of a thermostat from a YAML file
+    Set(SetScheduleCommand),
+}
+
+/// Week program as written by `schedule get` and read by `schedule set`.
+///
+/// Days missing in the file fall back to `default` or keep their current program.
+#[derive(Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct ScheduleFile {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    default: Option<Vec<FileSlot>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    monday: Option<Vec<FileSlot>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    tuesday: Option<Vec<FileSlot>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    wednesday: Option<Vec<FileSlot>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    thursday: Option<Vec<FileSlot>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    friday: Option<Vec<FileSlot>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    saturday: Option<Vec<FileSlot>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    sunday: Option<Vec<FileSlot>>,
+}
+
+/// Slot lasting until `until` (`HH:MM`, `24:00` for midnight).
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct FileSlot {
+    until: String,
+    temperature: f64,
+}
+
+impl ScheduleFile {
+    pub fn from_schedule(schedule: &WeekSchedule) -> Self {
+        let mut file = Self::default();
+        for day in Weekday::ALL {
+            *file.day_mut(day) = Some(
+                schedule
+                    .day(day)
+                    .iter()
+                    .map(|slot| FileSlot {
+                        until: format_time(slot.end_time),
+                        temperature: slot.temperature,
+                    })
+                    .collect(),
+            );
+        }
+        file
+    }
+
+    /// Overwrite the days of `schedule` given in this file.
+    pub fn apply_to(&self, schedule: &mut WeekSchedule) -> Result<(), Box<dyn Error + 'static>> {
+        for day in Weekday::ALL {
+            let slots = match self.day(day).or(self.default.as_ref()) {
+                Some(slots) => slots,
+                None => continue,
+            };
+            let slots = slots
+                .iter()
+                .map(|slot| {
+                    Ok(Slot {
+                        end_time: parse_time(&slot.until)?,
+                        temperature: slot.temperature,
+                    })
+                })
+                .collect::<Result<Vec<_>, Box<dyn Error + 'static>>>()?;
+            schedule.set_day(day, slots);
+        }
+        Ok(())
+    }
+
+    fn day(&self, day: Weekday) -> Option<&Vec<FileSlot>> {
+        match day {
+            Weekday::Monday => self.monday.as_ref(),
+            Weekday::Tuesday => self.tuesday.as_ref(),
+            Weekday::Wednesday => self.wednesday.as_ref(),
+            Weekday::Thursday => self.thursday.as_ref(),
+            Weekday::Friday => self.friday.as_ref(),
+            Weekday::Saturday => self.saturday.as_ref(),
+            Weekday::Sunday => self.sunday.as_ref(),
+        }
+    }
+
+    fn day_mut(&mut self, day: Weekday) -> &mut Option<Vec<FileSlot>> {
+        match day {
+            Weekday::Monday => &mut self.monday,
+            Weekday::Tuesday => &mut self.tuesday,
+            Weekday::Wednesday => &mut self.wednesday,
+            Weekday::Thursday => &mut self.thursday,
+            Weekday::Friday => &mut self.friday,
+            Weekday::Saturday => &mut self.saturday,
+            Weekday::Sunday => &mut self.sunday,
+        }
+    }
+}
+
+fn format_time(minutes: u16) -> String {
+    format!("{:02}:{:02}", minutes / 60, minutes % 60)
+}
+
+fn parse_time(time: &str) -> Result<u16, Box<dyn Error + 'static>> {
+    let (hours, minutes) = time
+        .split_once(':')
+        .ok_or_else(|| format!("invalid time {}, expected HH:MM", time))?;
+    let invalid = || format!("invalid time {}, expected HH:MM", time);
+    let hours = hours.trim().parse::<u16>().map_err(|_| invalid())?;
+    let minutes = minutes.trim().parse::<u16>().map_err(|_| invalid())?;
+    if hours > 24 || minutes > 59 {
+        return Err(invalid().into());
+    }
+    match hours
+        .checked_mul(60)
+        .and_then(|hours| hours.checked_add(minutes))
+    {
+        Some(minutes) if minutes <= MINUTES_PER_DAY => Ok(minutes),
+        _ => Err(invalid().into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn time() {
+        assert_eq!(parse_time("00:00").unwrap(), 0);
+        assert_eq!(parse_time("6:30").unwrap(), 390);
+        assert_eq!(parse_time("24:00").unwrap(), MINUTES_PER_DAY);
+        assert_eq!(format_time(parse_time("22:05").unwrap()), "22:05");
+
+        for invalid in [
+            "24:05", "25:00", "12:60", "1:99", "12", "ab:00", "-1:00", "9999:00",
+        ] {
+            assert!(parse_time(invalid).is_err(), "{}", invalid);
+        }
+    }
+}
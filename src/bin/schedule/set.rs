@@ -0,0 +1,66 @@
+use crate::schedule::ScheduleFile;
+use crate::Format;
+use clap::Args;
+use homematic_rs::{HomeMaticClient, ScheduleFormat, WeekSchedule};
+use std::error::Error;
+use std::fs;
+use std::path::PathBuf;
+
+#[derive(Args)]
+pub struct SetScheduleCommand {
+    /// address of the thermostat
+    address: String,
+    /// YAML file with the week program
+    file: PathBuf,
+
+    /// week program of HmIP thermostats
+    #[arg(long, default_value_t = 1)]
+    program: u8,
+
+    /// validate the file without writing it
+    #[arg(long)]
+    dry_run: bool,
+}
+
+impl SetScheduleCommand {
+    pub async fn exec(
+        &self,
+        client: &HomeMaticClient,
+        _format: Format,
+    ) -> Result<(), Box<dyn Error + 'static>> {
+        let file: ScheduleFile = serde_yaml::from_str(&fs::read_to_string(&self.file)?)?;
+
+        let mut buf = String::new();
+        let paramset = client
+            .get_paramset(&self.address, "MASTER", &mut buf)
+            .await?;
+        let schedule_format = ScheduleFormat::detect(&paramset, self.program)
+            .ok_or_else(|| format!("{} has no week program", self.address))?;
+        let mut schedule = WeekSchedule::from_paramset(&paramset, schedule_format)?;
+        let current = schedule.clone();
+
+        file.apply_to(&mut schedule)?;
+        schedule.validate(schedule_format)?;
+
+        if schedule == current {
+            println!("week program of {} is up to date", self.address);
+            return Ok(());
+        }
+        if self.dry_run {
+            println!("week program of {} is valid, not written", self.address);
+            return Ok(());
+        }
+
+        let mut buf = String::new();
+        client
+            .put_paramset(
+                &self.address,
+                "MASTER",
+                schedule.to_paramset(schedule_format),
+                &mut buf,
+            )
+            .await?;
+        println!("week program of {} written", self.address);
+        Ok(())
+    }
+}
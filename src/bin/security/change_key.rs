@@ -0,0 +1,32 @@
+use crate::security::read_passphrase;
+use crate::{confirm, Format};
+use clap::Args;
+use homematic_rs::HomeMaticClient;
+use std::error::Error;
+
+#[derive(Args)]
+pub struct ChangeKeyCommand {}
+
+impl ChangeKeyCommand {
+    pub async fn exec(
+        &self,
+        client: &HomeMaticClient,
+        _format: Format,
+    ) -> Result<(), Box<dyn Error + 'static>> {
+        let passphrase = read_passphrase("New passphrase")?;
+        if read_passphrase("Repeat new passphrase")? != passphrase {
+            return Err("passphrases differ".into());
+        }
+
+        println!("All devices with secured transmission will be switched to the new key.");
+        println!("Devices that are not reachable keep the old key and have to be re-keyed.");
+        if !confirm("Change the system key?")? {
+            return Err("aborted".into());
+        }
+
+        let mut buf = String::new();
+        client.change_key(&passphrase, &mut buf).await?;
+
+        Ok(())
+    }
+}
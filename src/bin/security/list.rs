@@ -0,0 +1,66 @@
+use crate::Format;
+use clap::Args;
+use comfy_table::Table;
+use homematic_rs::HomeMaticClient;
+use serde::Serialize;
+use std::error::Error;
+
+#[derive(Args)]
+pub struct ListSecurityCommand {
+    /// Also show channels without secured transmission
+    #[arg(long)]
+    all: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct OutputChannelSecurity {
+    #[serde(rename = "type")]
+    ty: String,
+    address: String,
+    aes: bool,
+}
+
+impl ListSecurityCommand {
+    pub async fn exec(
+        &self,
+        client: &HomeMaticClient,
+        format: Format,
+    ) -> Result<(), Box<dyn Error + 'static>> {
+        let mut buf = String::new();
+        let channels: Vec<OutputChannelSecurity> = client
+            .list_devices(&mut buf)
+            .await?
+            .into_iter()
+            .filter(|dev| dev.aes_active.is_some())
+            .map(|dev| OutputChannelSecurity {
+                aes: dev.aes_active.unwrap_or_default(),
+                ty: dev.ty,
+                address: dev.address,
+            })
+            .filter(|channel| self.all || channel.aes)
+            .collect();
+
+        match format {
+            Format::Table => {
+                let mut table = Table::new();
+                table
+                    .set_header(vec!["TYPE", "ADDRESS", "AES"])
+                    .load_preset(comfy_table::presets::NOTHING);
+
+                for channel in &channels {
+                    table.add_row(vec![
+                        channel.ty.as_str(),
+                        channel.address.as_str(),
+                        if channel.aes { "active" } else { "" },
+                    ]);
+                }
+
+                println!("{table}");
+            }
+            Format::Json => serde_json::to_writer_pretty(std::io::stdout(), &channels)?,
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,32 @@
+use crate::Format;
+use clap::Args;
+use homematic_rs::HomeMaticClient;
+use std::error::Error;
+
+#[derive(Args)]
+pub struct KeyMismatchCommand {
+    /// Clear the reported device afterwards
+    #[arg(long)]
+    reset: bool,
+}
+
+impl KeyMismatchCommand {
+    pub async fn exec(
+        &self,
+        client: &HomeMaticClient,
+        format: Format,
+    ) -> Result<(), Box<dyn Error + 'static>> {
+        let mut buf = String::new();
+        let device = client.get_key_mismatch_device(self.reset, &mut buf).await?;
+
+        match format {
+            Format::Table => match &device {
+                Some(device) => println!("{}", device),
+                None => println!("No device with key mismatch"),
+            },
+            Format::Json => serde_json::to_writer_pretty(std::io::stdout(), &device)?,
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,40 @@
+use std::error::Error;
+use std::io::{BufRead, Write};
+
+use clap::Subcommand;
+
+use crate::security::change_key::ChangeKeyCommand;
+use crate::security::list::ListSecurityCommand;
+use crate::security::mismatch::KeyMismatchCommand;
+use crate::security::rekey::RekeyCommand;
+
+pub mod change_key;
+pub mod list;
+pub mod mismatch;
+pub mod rekey;
+
+#[derive(Subcommand)]
+pub enum SecurityCommand {
+    /// Lists channels with secured transmission (AES)
+    List(ListSecurityCommand),
+    /// Report devices with an AES key mismatch
+    Mismatch(KeyMismatchCommand),
+    /// Re-key devices still using an old key with a temporary key
+    Rekey(RekeyCommand),
+    /// Change the AES system key
+    ChangeKey(ChangeKeyCommand),
+}
+
+/// Read a passphrase from the terminal.
+pub fn read_passphrase(prompt: &str) -> Result<String, Box<dyn Error + 'static>> {
+    eprint!("{}: ", prompt);
+    std::io::stderr().flush()?;
+
+    let mut passphrase = String::new();
+    std::io::stdin().lock().read_line(&mut passphrase)?;
+    let passphrase = passphrase.trim_end_matches(['\r', '\n']).to_string();
+    if passphrase.is_empty() {
+        return Err("empty passphrase".into());
+    }
+    Ok(passphrase)
+}
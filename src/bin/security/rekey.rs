@@ -0,0 +1,63 @@
+use crate::security::read_passphrase;
+use crate::Format;
+use clap::Args;
+use homematic_rs::HomeMaticClient;
+use std::error::Error;
+use std::time::{Duration, Instant};
+
+#[derive(Args)]
+pub struct RekeyCommand {
+    /// Time in seconds to watch for the key mismatch to recur after operating the device
+    #[arg(long, default_value_t = 30)]
+    timeout: u64,
+}
+
+impl RekeyCommand {
+    pub async fn exec(
+        &self,
+        client: &HomeMaticClient,
+        _format: Format,
+    ) -> Result<(), Box<dyn Error + 'static>> {
+        let mut buf = String::new();
+        let device = match client.get_key_mismatch_device(false, &mut buf).await? {
+            Some(device) => device,
+            None => {
+                println!("No device with key mismatch");
+                return Ok(());
+            }
+        };
+
+        println!("{} uses a different AES key.", device);
+        let passphrase = read_passphrase("Old passphrase of the device")?;
+
+        // the CCU reports the device until the mismatch is reset
+        client.get_key_mismatch_device(true, &mut buf).await?;
+        client.set_temp_key(&passphrase, &mut buf).await?;
+
+        println!(
+            "Operate {} now (e.g. press a button) so it can be switched to the system key, \
+             then press Enter.",
+            device
+        );
+        std::io::stdin().read_line(&mut String::new())?;
+
+        let deadline = Instant::now() + Duration::from_secs(self.timeout);
+        while Instant::now() < deadline {
+            tokio::time::sleep(Duration::from_secs(5)).await;
+
+            if let Some(mismatch) = client.get_key_mismatch_device(false, &mut buf).await? {
+                if mismatch == device {
+                    return Err(format!("{} still uses a different key", device).into());
+                }
+            }
+        }
+
+        // the CCU does not confirm the key exchange, a device that was not operated reports nothing
+        println!(
+            "No key mismatch of {} reported within {} s, operate it again to check that it \
+             accepts the system key",
+            device, self.timeout
+        );
+        Ok(())
+    }
+}
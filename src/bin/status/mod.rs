@@ -0,0 +1,143 @@
+use crate::Format;
+use clap::Args;
+use comfy_table::Table;
+use homematic_rs::HomeMaticClient;
+use serde::Serialize;
+use std::error::Error;
+use std::time::Instant;
+
+#[derive(Args)]
+pub struct StatusCommand {
+    /// List documented methods the interface does not support
+    #[arg(long)]
+    methods: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct OutputStatus {
+    reachable: bool,
+    latency_ms: Option<u128>,
+    error: Option<String>,
+    version: Option<String>,
+    supported_methods: Vec<&'static str>,
+    unsupported_methods: Vec<&'static str>,
+    additional_methods: Vec<String>,
+    interfaces: Vec<OutputStatusInterface>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct OutputStatusInterface {
+    address: String,
+    description: String,
+    connected: bool,
+    default: bool,
+}
+
+impl StatusCommand {
+    pub async fn exec(
+        &self,
+        client: &HomeMaticClient,
+        format: Format,
+    ) -> Result<(), Box<dyn Error + 'static>> {
+        let status = self.status(client).await;
+
+        match format {
+            Format::Table => {
+                match (&status.error, status.latency_ms) {
+                    (Some(err), _) => println!("reachable: no ({})", err),
+                    (None, Some(latency)) => println!("reachable: yes ({} ms)", latency),
+                    (None, None) => println!("reachable: yes"),
+                }
+                if let Some(version) = &status.version {
+                    println!("version:   {}", version);
+                }
+                if status.reachable {
+                    println!(
+                        "methods:   {} of {} documented methods supported",
+                        status.supported_methods.len(),
+                        status.supported_methods.len() + status.unsupported_methods.len()
+                    );
+                }
+                if self.methods && !status.unsupported_methods.is_empty() {
+                    println!("unsupported: {}", status.unsupported_methods.join(", "));
+                }
+
+                if !status.interfaces.is_empty() {
+                    let mut table = Table::new();
+                    table
+                        .set_header(vec!["INTERFACE", "DESCRIPTION", "CONNECTED", "DEFAULT"])
+                        .load_preset(comfy_table::presets::NOTHING);
+                    for interface in &status.interfaces {
+                        table.add_row(vec![
+                            interface.address.clone(),
+                            interface.description.clone(),
+                            interface.connected.to_string(),
+                            interface.default.to_string(),
+                        ]);
+                    }
+                    println!("{table}");
+                }
+            }
+            Format::Json => serde_json::to_writer_pretty(std::io::stdout(), &status)?,
+        }
+
+        match status.error {
+            Some(err) => Err(err.into()),
+            None => Ok(()),
+        }
+    }
+
+    async fn status(&self, client: &HomeMaticClient) -> OutputStatus {
+        let mut status = OutputStatus {
+            reachable: false,
+            latency_ms: None,
+            error: None,
+            version: None,
+            supported_methods: vec![],
+            unsupported_methods: vec![],
+            additional_methods: vec![],
+            interfaces: vec![],
+        };
+
+        let mut buf = String::new();
+        let capabilities = match client.probe_capabilities(&mut buf).await {
+            Ok(capabilities) => capabilities,
+            Err(err) => {
+                status.error = Some(err.to_string());
+                return status;
+            }
+        };
+        status.reachable = true;
+
+        if capabilities.supports("ping") {
+            let start = Instant::now();
+            match client.ping("homematicctl", &mut buf).await {
+                Ok(_) => status.latency_ms = Some(start.elapsed().as_millis()),
+                Err(err) => status.error = Some(err.to_string()),
+            }
+        }
+        if capabilities.supports("getVersion") {
+            status.version = client.get_version(&mut buf).await.ok();
+        }
+        if capabilities.supports("listBidcosInterfaces") {
+            if let Ok(interfaces) = client.list_bidcos_interfaces(&mut buf).await {
+                status.interfaces = interfaces
+                    .into_iter()
+                    .map(|interface| OutputStatusInterface {
+                        address: interface.address,
+                        description: interface.description,
+                        connected: interface.connected,
+                        default: interface.default,
+                    })
+                    .collect();
+            }
+        }
+
+        status.supported_methods = capabilities.supported;
+        status.unsupported_methods = capabilities.unsupported;
+        status.additional_methods = capabilities.additional;
+        status
+    }
+}
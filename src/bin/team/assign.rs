@@ -0,0 +1,60 @@
+use crate::Format;
+use clap::Args;
+use homematic_rs::HomeMaticClient;
+use std::error::Error;
+
+#[derive(Args)]
+pub struct AssignTeamCommand {
+    /// Channel address
+    channel: String,
+
+    /// Address of the team channel
+    #[arg(required_unless_present = "remove")]
+    team: Option<String>,
+
+    /// Remove the channel from its team
+    #[arg(long, conflicts_with = "team")]
+    remove: bool,
+}
+
+impl AssignTeamCommand {
+    pub async fn exec(
+        &self,
+        client: &HomeMaticClient,
+        _format: Format,
+    ) -> Result<(), Box<dyn Error + 'static>> {
+        let mut buf = String::new();
+
+        let team = match &self.team {
+            Some(team) => team,
+            None => {
+                client.set_team(&self.channel, "", &mut buf).await?;
+                return Ok(());
+            }
+        };
+
+        let channel = client
+            .get_device_description(&self.channel, &mut buf)
+            .await?;
+        let team = client.get_device_description(team, &mut buf).await?;
+        if channel.team_tag.is_none() {
+            return Err(format!("{} cannot be assigned to a team", channel.address).into());
+        }
+        if channel.team_tag != team.team_tag {
+            return Err(format!(
+                "team tags differ: {} has {}, {} has {}",
+                channel.address,
+                channel.team_tag.as_deref().unwrap_or("none"),
+                team.address,
+                team.team_tag.as_deref().unwrap_or("none"),
+            )
+            .into());
+        }
+
+        client
+            .set_team(&channel.address, &team.address, &mut buf)
+            .await?;
+
+        Ok(())
+    }
+}
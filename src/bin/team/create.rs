@@ -0,0 +1,25 @@
+use crate::Format;
+use clap::Args;
+use homematic_rs::HomeMaticClient;
+use std::error::Error;
+
+#[derive(Args)]
+pub struct CreateTeamCommand {
+    /// Type of the virtual team device (e.g. HM-Sec-SD-Team)
+    device_type: String,
+}
+
+impl CreateTeamCommand {
+    pub async fn exec(
+        &self,
+        client: &HomeMaticClient,
+        _format: Format,
+    ) -> Result<(), Box<dyn Error + 'static>> {
+        let mut buf = String::new();
+        let address = client
+            .add_virtual_device_instance(&self.device_type, &mut buf)
+            .await?;
+        println!("{}", address);
+        Ok(())
+    }
+}
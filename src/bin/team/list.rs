@@ -0,0 +1,63 @@
+use crate::Format;
+use clap::Args;
+use comfy_table::Table;
+use homematic_rs::HomeMaticClient;
+use serde::Serialize;
+use std::error::Error;
+
+#[derive(Args)]
+pub struct ListTeamsCommand {}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct OutputTeam {
+    #[serde(rename = "type")]
+    ty: String,
+    address: String,
+    team_tag: Option<String>,
+    channels: Vec<String>,
+}
+
+impl ListTeamsCommand {
+    pub async fn exec(
+        &self,
+        client: &HomeMaticClient,
+        format: Format,
+    ) -> Result<(), Box<dyn Error + 'static>> {
+        let mut buf = String::new();
+        let teams: Vec<OutputTeam> = client
+            .list_teams(&mut buf)
+            .await?
+            .into_iter()
+            .map(|team| OutputTeam {
+                ty: team.ty,
+                address: team.address,
+                team_tag: team.team_tag,
+                channels: team.team_channels.unwrap_or_default(),
+            })
+            .collect();
+
+        match format {
+            Format::Table => {
+                let mut table = Table::new();
+                table
+                    .set_header(vec!["TYPE", "ADDRESS", "TAG", "CHANNELS"])
+                    .load_preset(comfy_table::presets::NOTHING);
+
+                for team in &teams {
+                    table.add_row(vec![
+                        &team.ty,
+                        &team.address,
+                        team.team_tag.as_deref().unwrap_or(""),
+                        &team.channels.join(", "),
+                    ]);
+                }
+
+                println!("{table}");
+            }
+            Format::Json => serde_json::to_writer_pretty(std::io::stdout(), &teams)?,
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,19 @@
+use clap::Subcommand;
+
+use crate::team::assign::AssignTeamCommand;
+use crate::team::create::CreateTeamCommand;
+use crate::team::list::ListTeamsCommand;
+
+pub mod assign;
+pub mod create;
+pub mod list;
+
+#[derive(Subcommand)]
+pub enum TeamCommand {
+    /// Lists teams and their channels
+    List(ListTeamsCommand),
+    /// Assign a channel to a team
+    Assign(AssignTeamCommand),
+    /// Create a new team
+    Create(CreateTeamCommand),
+}
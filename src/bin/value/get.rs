@@ -0,0 +1,34 @@
+use crate::value::describe_value;
+use crate::Format;
+use clap::Args;
+use homematic_rs::HomeMaticClient;
+use std::error::Error;
+
+#[derive(Args)]
+pub struct GetValueCommand {
+    /// channel address
+    address: String,
+    /// value key
+    key: String,
+}
+
+impl GetValueCommand {
+    pub async fn exec(
+        &self,
+        client: &HomeMaticClient,
+        format: Format,
+    ) -> Result<(), Box<dyn Error + 'static>> {
+        let mut buf = String::new();
+        let value = client.get_value(&self.address, &self.key, &mut buf).await?;
+
+        match format {
+            Format::Table => {
+                let description = describe_value(client, &self.address, &self.key).await?;
+                println!("{}", description.format_value(&value))
+            }
+            Format::Json => serde_json::to_writer_pretty(std::io::stdout(), &value)?,
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,60 @@
+use std::error::Error;
+
+use clap::Subcommand;
+use xrs_xmlrpc::value::Value;
+
+use homematic_rs::{HomeMaticClient, ParameterDescription};
+
+use crate::value::get::GetValueCommand;
+use crate::value::press::PressValueCommand;
+use crate::value::set::SetValueCommand;
+
+pub mod get;
+pub mod press;
+pub mod set;
+
+#[derive(Subcommand)]
+pub enum ValueCommand {
+    /// Read a single value of the VALUES paramset
+    Get(GetValueCommand),
+    /// Write a single value of the VALUES paramset
+    Set(SetValueCommand),
+    /// Trigger an ACTION parameter (e.g. PRESS_SHORT)
+    Press(PressValueCommand),
+}
+
+/// Render a value for table output.
+pub fn format_value(value: &Value) -> String {
+    match value {
+        Value::Int(v) => v.to_string(),
+        Value::Bool(v) => v.to_string(),
+        Value::Double(v) => v.to_string(),
+        Value::String(v) => v.to_string(),
+        other => format!("{:?}", other),
+    }
+}
+
+/// Look up the description of a parameter of the VALUES paramset.
+pub async fn describe_value(
+    client: &HomeMaticClient,
+    address: &str,
+    value_key: &str,
+) -> Result<ParameterDescription, Box<dyn Error + 'static>> {
+    let mut buf = String::new();
+    client
+        .get_paramset_description(address, "VALUES", &mut buf)
+        .await?
+        .remove(value_key)
+        .ok_or_else(|| format!("{} has no value {}", address, value_key).into())
+}
+
+/// Convert a command line argument into a value of the type expected by the parameter.
+pub fn coerce_value(
+    description: &ParameterDescription,
+    raw: &str,
+) -> Result<Value<'static>, Box<dyn Error + 'static>> {
+    if !description.is_writable() {
+        return Err("parameter is not writable".into());
+    }
+    Ok(description.parse_value(raw)?)
+}
@@ -0,0 +1,35 @@
+use crate::value::describe_value;
+use crate::Format;
+use clap::Args;
+use homematic_rs::{HomeMaticClient, ParameterDescription};
+use std::error::Error;
+use xrs_xmlrpc::value::Value;
+
+#[derive(Args)]
+pub struct PressValueCommand {
+    /// channel address
+    address: String,
+    /// action key
+    #[arg(default_value = "PRESS_SHORT")]
+    key: String,
+}
+
+impl PressValueCommand {
+    pub async fn exec(
+        &self,
+        client: &HomeMaticClient,
+        _format: Format,
+    ) -> Result<(), Box<dyn Error + 'static>> {
+        match describe_value(client, &self.address, &self.key).await? {
+            ParameterDescription::Action(_) => {}
+            _ => return Err(format!("{} is not an ACTION parameter", self.key).into()),
+        }
+
+        let mut buf = String::new();
+        client
+            .set_value(&self.address, &self.key, Value::Bool(true), &mut buf)
+            .await?;
+
+        Ok(())
+    }
+}
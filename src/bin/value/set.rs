@@ -0,0 +1,34 @@
+use crate::value::{coerce_value, describe_value};
+use crate::Format;
+use clap::Args;
+use homematic_rs::HomeMaticClient;
+use std::error::Error;
+
+#[derive(Args)]
+pub struct SetValueCommand {
+    /// channel address
+    address: String,
+    /// value key
+    key: String,
+    /// new value
+    value: String,
+}
+
+impl SetValueCommand {
+    pub async fn exec(
+        &self,
+        client: &HomeMaticClient,
+        _format: Format,
+    ) -> Result<(), Box<dyn Error + 'static>> {
+        let description = describe_value(client, &self.address, &self.key).await?;
+        let value = coerce_value(&description, &self.value)
+            .map_err(|err| format!("{}: {}", self.key, err))?;
+
+        let mut buf = String::new();
+        client
+            .set_value(&self.address, &self.key, value, &mut buf)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,156 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::io::Write;
+
+use clap::Args;
+use homematic_rs::{Event, HomeMaticClient, ParameterDescription, ParamsetDescription, ValueEvent};
+use serde::Serialize;
+use xrs_xmlrpc::value::Value;
+
+use crate::events::{CallbackArgs, Subscription};
+use crate::value::format_value;
+use crate::Format;
+
+#[derive(Args)]
+pub struct WatchCommand {
+    /// only show channels whose address starts with this prefix
+    #[arg(long)]
+    address: Option<String>,
+
+    /// only show devices of this type
+    #[arg(long = "type")]
+    ty: Option<String>,
+
+    /// only show these value keys
+    #[arg(long)]
+    key: Vec<String>,
+
+    #[command(flatten)]
+    callback: CallbackArgs,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct OutputValueEvent<'a> {
+    address: &'a str,
+    device_type: Option<&'a str>,
+    key: &'a str,
+    value: &'a Value<'static>,
+}
+
+impl WatchCommand {
+    pub async fn exec(
+        &self,
+        client: &HomeMaticClient,
+        format: Format,
+    ) -> Result<(), Box<dyn Error + 'static>> {
+        let device_types = device_types(client).await?;
+        let mut descriptions: HashMap<String, ParamsetDescription> = HashMap::new();
+
+        let mut subscription = Subscription::start(client, &self.callback).await?;
+        if format == Format::Table {
+            println!("{:<24} {:<16} {:<24} VALUE", "ADDRESS", "TYPE", "KEY");
+        }
+
+        let result = loop {
+            let event = tokio::select! {
+                _ = tokio::signal::ctrl_c() => break Ok(()),
+                event = subscription.next() => match event {
+                    Some(event) => event,
+                    None => break Ok(()),
+                },
+            };
+
+            if let Event::Value(event) = event {
+                let device_type = device_types.get(&event.address).map(|ty| ty as &str);
+                if !self.matches(&event, device_type) {
+                    continue;
+                }
+                if format == Format::Table && !descriptions.contains_key(&event.address) {
+                    let mut buf = String::new();
+                    let description = client
+                        .get_paramset_description(&event.address, "VALUES", &mut buf)
+                        .await
+                        .unwrap_or_default();
+                    descriptions.insert(event.address.clone(), description);
+                }
+                let description = descriptions
+                    .get(&event.address)
+                    .and_then(|description| description.get(&event.value_key));
+                // e.g. a closed pipe, the subscription must still be removed
+                if let Err(err) = print_event(&event, device_type, description, format) {
+                    break Err(err);
+                }
+            }
+        };
+
+        subscription.stop().await?;
+        result
+    }
+
+    fn matches(&self, event: &ValueEvent, device_type: Option<&str>) -> bool {
+        if let Some(prefix) = &self.address {
+            if !event.address.starts_with(prefix.as_str()) {
+                return false;
+            }
+        }
+        if let Some(ty) = &self.ty {
+            if device_type != Some(ty.as_str()) {
+                return false;
+            }
+        }
+        self.key.is_empty() || self.key.contains(&event.value_key)
+    }
+}
+
+/// Map device and channel addresses to the type of their device.
+async fn device_types(
+    client: &HomeMaticClient,
+) -> Result<HashMap<String, String>, Box<dyn Error + 'static>> {
+    let mut buf = String::new();
+    Ok(client
+        .list_devices(&mut buf)
+        .await?
+        .into_iter()
+        .map(|dev| {
+            let ty = match dev.parent_type {
+                Some(parent_type) if !dev.parent.is_empty() => parent_type,
+                _ => dev.ty,
+            };
+            (dev.address, ty)
+        })
+        .collect())
+}
+
+fn print_event(
+    event: &ValueEvent,
+    device_type: Option<&str>,
+    description: Option<&ParameterDescription>,
+    format: Format,
+) -> Result<(), Box<dyn Error + 'static>> {
+    let mut stdout = std::io::stdout().lock();
+    match format {
+        Format::Table => writeln!(
+            stdout,
+            "{:<24} {:<16} {:<24} {}",
+            event.address,
+            device_type.unwrap_or("-"),
+            event.value_key,
+            match description {
+                Some(description) => description.format_value(&event.value),
+                None => format_value(&event.value),
+            }
+        )?,
+        Format::Json => writeln!(
+            stdout,
+            "{}",
+            serde_json::to_string(&OutputValueEvent {
+                address: &event.address,
+                device_type,
+                key: &event.value_key,
+                value: &event.value,
+            })?
+        )?,
+    }
+    Ok(())
+}
@@ -2,6 +2,8 @@ use bitflags::bitflags;
 use serde::{Deserialize, Deserializer};
 use xrs_xmlrpc::value;
 
+use crate::maintenance::MAINTENANCE_CHANNEL_TYPE;
+
 bitflags! {
     /// Or-connection of flags for UI display.
     struct DeviceFlags: i32 {
@@ -259,6 +261,39 @@ impl DeviceDescription {
     pub fn is_deletable(&self) -> bool {
         !self.flags.contains(DeviceFlags::DontDelete)
     }
+
+    /// This object is a device and not a channel.
+    pub fn is_device(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    /// This object is the maintenance channel (channel 0) of a device.
+    pub fn is_maintenance_channel(&self) -> bool {
+        !self.is_device() && (self.ty == MAINTENANCE_CHANNEL_TYPE || self.index == Some(0))
+    }
+
+    /// Address of the maintenance channel of this device.
+    ///
+    /// Only for devices.
+    pub fn maintenance_channel_address(&self) -> Option<&str> {
+        if !self.is_device() {
+            return None;
+        }
+        self.children
+            .iter()
+            .map(|address| address.as_str())
+            .find(|address| address.rsplit_once(':').map(|(_, index)| index) == Some("0"))
+    }
+
+    /// Find the maintenance channel of this device in `descriptions` (e.g. from `list_devices`).
+    pub fn maintenance_channel<'a>(
+        &self,
+        descriptions: &'a [DeviceDescription],
+    ) -> Option<&'a DeviceDescription> {
+        descriptions
+            .iter()
+            .find(|dev| dev.parent == self.address && dev.is_maintenance_channel())
+    }
 }
 
 bitflags! {
@@ -273,8 +308,11 @@ bitflags! {
     }
 }
 
-#[derive(Debug)]
+/// Mode of the install mode.
+#[derive(Debug, Clone, Copy)]
 pub enum InstallMode {
+    /// Pair new devices.
     Normal = 1,
+    /// Pair devices and reset them to factory state first.
     Reset = 2,
 }
@@ -0,0 +1,79 @@
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// RSSI values reported when the reception strength is unknown.
+pub(crate) const RSSI_UNKNOWN: [i32; 2] = [65536, -65535];
+
+/// BidCos-RF interface (CCU radio module or LAN gateway).
+#[derive(Deserialize, Debug)]
+pub struct BidcosInterface {
+    /// Serial number of the interface.
+    #[serde(rename = "ADDRESS")]
+    pub address: String,
+
+    /// Textual description of the interface.
+    #[serde(rename = "DESCRIPTION", default)]
+    pub description: String,
+
+    /// Indicates whether the interface is connected to the interface process.
+    #[serde(rename = "CONNECTED")]
+    pub connected: bool,
+
+    /// Indicates whether this is the default interface.
+    ///
+    /// New devices are assigned to the default interface.
+    #[serde(rename = "DEFAULT")]
+    pub default: bool,
+
+    /// Used duty cycle of the interface in percent.
+    ///
+    /// Optional.
+    #[serde(rename = "DUTY_CYCLE")]
+    pub duty_cycle: Option<i32>,
+}
+
+/// Device types of HmIP access points reporting DUTY_CYCLE_LEVEL on their maintenance channel.
+///
+/// HmIP-RCV-50 is the virtual device of the radio module of the CCU.
+pub(crate) const HMIP_ACCESS_POINT_TYPES: &[&str] = &[
+    "HmIP-RCV-50",
+    "HmIP-HAP",
+    "HmIP-HAP-A",
+    "HmIP-DRAP",
+    "HmIPW-DRAP",
+];
+
+/// Used duty cycle of a radio interface or HmIP access point.
+#[derive(Debug)]
+pub struct DutyCycle {
+    /// Address of the interface or access point.
+    pub address: String,
+
+    /// Description of the interface or type of the access point.
+    pub description: String,
+
+    /// Indicates whether the interface is connected.
+    pub connected: bool,
+
+    /// Used duty cycle in percent.
+    pub level: Option<f64>,
+}
+
+/// Reception strengths between a device and a peer (interface or other device) in dBm.
+#[derive(Deserialize, Debug, Clone, Copy)]
+pub struct RssiPair(i32, i32);
+
+impl RssiPair {
+    /// Reception strength of the peer at the device.
+    pub fn device_rssi(&self) -> Option<i32> {
+        Some(self.0).filter(|rssi| !RSSI_UNKNOWN.contains(rssi))
+    }
+
+    /// Reception strength of the device at the peer.
+    pub fn peer_rssi(&self) -> Option<i32> {
+        Some(self.1).filter(|rssi| !RSSI_UNKNOWN.contains(rssi))
+    }
+}
+
+/// Reception strengths by device address and peer address.
+pub type RssiInfo = HashMap<String, HashMap<String, RssiPair>>;
@@ -2,19 +2,43 @@ use std::collections::HashMap;
 use std::time::Duration;
 
 use bitflags::bitflags;
+use log::warn;
 use serde::{Deserialize, Deserializer, Serialize};
 use xrs_xmlrpc::client::reqwest::XmlRpcClient;
 use xrs_xmlrpc::value::Value;
 use xrs_xmlrpc::XmlRpcError;
 
+use crate::multicall::{MethodCall, MulticallResult};
+
 pub(crate) mod de;
 mod device;
+mod event;
+mod interface;
+mod link;
+mod maintenance;
+mod multicall;
 mod param;
+mod profiles;
+mod schedule;
+mod server;
 mod service_message;
+mod unit;
 
-use crate::device::InstallMode;
+pub use crate::event::{Event, EventServer, ValueEvent};
+pub use crate::interface::{BidcosInterface, DutyCycle, RssiInfo, RssiPair};
+pub use crate::link::{GetLinksFlags, Link};
+pub use crate::maintenance::MaintenanceStatus;
+pub use crate::profiles::{
+    Blind, ControlMode, DeviceProfile, Dimmer, LevelValues, ProfileError, Switch, SwitchValues,
+    Thermostat, ThermostatValues, Variant,
+};
+pub use crate::schedule::{
+    ScheduleError, ScheduleFormat, Slot, WeekSchedule, Weekday, MINUTES_PER_DAY,
+};
+pub use crate::server::{Capabilities, DOCUMENTED_METHODS};
 pub use crate::service_message::ServiceMessage;
-pub use device::{ChannelDirection, DeviceDeleteFlags, DeviceDescription, RxMode};
+pub use crate::unit::{ParseValueError, Unit};
+pub use device::{ChannelDirection, DeviceDeleteFlags, DeviceDescription, InstallMode, RxMode};
 pub use param::{
     BoolParameterDescription, EnumParameterDescription, FloatParameterDescription,
     IntegerParameterDescription, ParameterDescription, Paramset, ParamsetDescription, SpecialF32,
@@ -28,67 +52,50 @@ pub struct HomeMaticClient {
     xmlrpc: XmlRpcClient,
 }
 
-/*
-    "abortDeleteDevice",
-    "activateLinkParamset",
-    "addDevice",
-    "addLink",
-    "addVirtualDeviceInstance",
-    "changeKey",
-    "clearConfigCache",
-    "deleteDevice",
-    "deleteVolatileMetadata",
-    "determine_parameter",
-    "exit",
-    "getAllMetadata",
-    "getDeviceDescription",
-    "getInstallMode",
-    "getKeyMismatchDevice",
-    "getLinkInfo",
-    "getLinkPeers",
-    "getLinks",
-    "getMetadata",
-    "getParamset",
-    "getParamsetDescription",
-    "getParamsetId",
-    "getServiceMessages",
-    "getValue",
-    "getVersion",
-    "getVolatileMetadata",
-    "hasVolatileMetadata",
-    "init",
-    "listBidcosInterfaces",
-    "listDevices",
-    "listReplaceableDevices",
-    "listTeams",
-    "logLevel",
-    "ping",
-    "putParamset",
-    "refreshDeployedDeviceFirmwareList",
-    "removeLink",
-    "replaceDevice",
-    "reportValueUsage",
-    "restoreConfigToDevice",
-    "rssiInfo",
-    "setBidcosInterface",
-    "set_install_mode",
-    "setInterfaceClock",
-    "setLinkInfo",
-    "setMetadata",
-    "setRFLGWInfoLED",
-    "setTeam",
-    "setTempKey",
-    "setValue",
-    "setVolatileMetadata",
-    "updateFirmware",
-]
- */
-
 impl HomeMaticClient {
     pub fn new(xmlrpc: XmlRpcClient) -> Self {
         Self { xmlrpc }
     }
 
+    /// Register a logic layer under `url` to receive events with [`EventServer`].
+    ///
+    /// `interface_id` is sent back with every event.
+    pub async fn init(
+        &self,
+        url: &str,
+        interface_id: &str,
+        buf: &mut String,
+    ) -> Result<(), XmlRpcError> {
+        self.xmlrpc.call("init", &(url, interface_id), buf).await
+    }
+
+    /// Unregister the logic layer registered under `url`.
+    pub async fn deinit(&self, url: &str, buf: &mut String) -> Result<(), XmlRpcError> {
+        self.xmlrpc.call("init", &(url, ""), buf).await
+    }
+
+    /// Check that the interface process is alive.
+    ///
+    /// Registered logic layers receive a PONG event with `caller_id` as value.
+    pub async fn ping(&self, caller_id: &str, buf: &mut String) -> Result<bool, XmlRpcError> {
+        self.xmlrpc.call("ping", &(caller_id,), buf).await
+    }
+
+    /// Version of the interface process.
+    pub async fn get_version(&self, buf: &mut String) -> Result<String, XmlRpcError> {
+        self.xmlrpc.call("getVersion", &(), buf).await
+    }
+
+    /// Names of all methods the interface process implements.
+    pub async fn list_methods(&self, buf: &mut String) -> Result<Vec<String>, XmlRpcError> {
+        self.xmlrpc.call("system.listMethods", &(), buf).await
+    }
+
+    /// Determine which documented methods the interface process implements.
+    pub async fn probe_capabilities(&self, buf: &mut String) -> Result<Capabilities, XmlRpcError> {
+        Ok(Capabilities::from_methods(self.list_methods(buf).await?))
+    }
+
     pub async fn list_devices(
         &self,
         buf: &mut String,
@@ -151,6 +158,30 @@ impl HomeMaticClient {
             .await
     }
 
+    /// Read several paramsets with a single `system.multicall` request.
+    ///
+    /// `requests` are pairs of address and paramset type. The result holds the paramset or the
+    /// fault message of each request in the same order.
+    pub async fn get_paramsets<'a>(
+        &self,
+        requests: &[(&str, &str)],
+        buf: &'a mut String,
+    ) -> Result<Vec<Result<Paramset<'a>, String>>, XmlRpcError> {
+        let calls: Vec<MethodCall> = requests
+            .iter()
+            .map(|(address, paramset_type)| MethodCall {
+                method_name: "getParamset",
+                params: vec![address, paramset_type],
+            })
+            .collect();
+        let results: Vec<MulticallResult<Paramset<'a>>> =
+            self.xmlrpc.call("system.multicall", &(calls,), buf).await?;
+        Ok(results
+            .into_iter()
+            .map(MulticallResult::into_result)
+            .collect())
+    }
+
     pub async fn get_value<'a>(
         &self,
         address: &str,
@@ -211,6 +242,81 @@ impl HomeMaticClient {
             .await
     }
 
+    /// Links of the channel or of all channels of the device with `address`.
+    pub async fn get_links(
+        &self,
+        address: &str,
+        flags: GetLinksFlags,
+        buf: &mut String,
+    ) -> Result<Vec<Link>, XmlRpcError> {
+        self.xmlrpc
+            .call("getLinks", &(address, flags.bits()), buf)
+            .await
+    }
+
+    /// Replace the device `old_device_address` by the newly paired device `new_device_address`.
+    ///
+    /// Links and configuration of the old device are transferred to the new device.
+    pub async fn replace_device(
+        &self,
+        old_device_address: &str,
+        new_device_address: &str,
+        buf: &mut String,
+    ) -> Result<(), XmlRpcError> {
+        self.xmlrpc
+            .call(
+                "replaceDevice",
+                &(old_device_address, new_device_address),
+                buf,
+            )
+            .await
+    }
+
+    /// Devices that can be replaced by the device `new_device_address`.
+    pub async fn list_replaceable_devices(
+        &self,
+        new_device_address: &str,
+        buf: &mut String,
+    ) -> Result<Vec<DeviceDescription>, XmlRpcError> {
+        self.xmlrpc
+            .call("listReplaceableDevices", &(new_device_address,), buf)
+            .await
+    }
+
+    /// Transfer the configuration stored in the interface process to the device `address`.
+    pub async fn restore_config_to_device(
+        &self,
+        address: &str,
+        buf: &mut String,
+    ) -> Result<(), XmlRpcError> {
+        self.xmlrpc
+            .call("restoreConfigToDevice", &(address,), buf)
+            .await
+    }
+
+    /// Create a direct link from channel `sender` to channel `receiver`.
+    pub async fn add_link(
+        &self,
+        sender: &str,
+        receiver: &str,
+        name: &str,
+        description: &str,
+        buf: &mut String,
+    ) -> Result<(), XmlRpcError> {
+        self.xmlrpc
+            .call("addLink", &(sender, receiver, name, description), buf)
+            .await
+    }
+
+    /// All metadata stored for the object `object_id` (e.g. a device or channel address).
+    pub async fn get_all_metadata<'a>(
+        &self,
+        object_id: &str,
+        buf: &'a mut String,
+    ) -> Result<HashMap<String, Value<'a>>, XmlRpcError> {
+        self.xmlrpc.call("getAllMetadata", &(object_id,), buf).await
+    }
+
     pub async fn set_install_mode(&self, on: bool, buf: &mut String) -> Result<(), XmlRpcError> {
         self.xmlrpc.call("setInstallMode", &(on,), buf).await
     }
@@ -244,16 +350,222 @@ impl HomeMaticClient {
         Ok(Duration::from_secs(remaining))
     }
 
-    pub async fn get_key_missmatch_device(
+    /// Start the firmware update of `devices`.
+    ///
+    /// Returns for every device whether the update was started.
+    pub async fn update_firmware(
         &self,
-        reset: bool,
+        devices: &[&str],
+        buf: &mut String,
+    ) -> Result<Vec<bool>, XmlRpcError> {
+        self.xmlrpc.call("updateFirmware", &(devices,), buf).await
+    }
+
+    /// Re-read the firmware files available for devices.
+    pub async fn refresh_deployed_device_firmware_list(
+        &self,
+        buf: &mut String,
+    ) -> Result<(), XmlRpcError> {
+        self.xmlrpc
+            .call("refreshDeployedDeviceFirmwareList", &(), buf)
+            .await
+    }
+
+    /// BidCos-RF interfaces known to the interface process.
+    pub async fn list_bidcos_interfaces(
+        &self,
+        buf: &mut String,
+    ) -> Result<Vec<BidcosInterface>, XmlRpcError> {
+        self.xmlrpc.call("listBidcosInterfaces", &(), buf).await
+    }
+
+    /// Assign the device `device_address` to the interface `interface_address`.
+    ///
+    /// With `roaming` the assignment is adapted automatically to the reception conditions.
+    pub async fn set_bidcos_interface(
+        &self,
+        device_address: &str,
+        interface_address: &str,
+        roaming: bool,
+        buf: &mut String,
+    ) -> Result<(), XmlRpcError> {
+        self.xmlrpc
+            .call(
+                "setBidcosInterface",
+                &(device_address, interface_address, roaming),
+                buf,
+            )
+            .await
+    }
+
+    /// Reception strengths between devices and interfaces.
+    pub async fn rssi_info(&self, buf: &mut String) -> Result<RssiInfo, XmlRpcError> {
+        self.xmlrpc.call("rssiInfo", &(), buf).await
+    }
+
+    /// Used duty cycle of all BidCos-RF interfaces and HmIP access points of this interface process.
+    ///
+    /// HmIP access points report their DUTY_CYCLE_LEVEL on the maintenance channel.
+    pub async fn duty_cycles(&self, buf: &mut String) -> Result<Vec<DutyCycle>, XmlRpcError> {
+        let interfaces = match self.list_bidcos_interfaces(buf).await {
+            Ok(interfaces) => interfaces,
+            Err(err) => {
+                // HmIP-RF does not implement listBidcosInterfaces
+                let methods = self.list_methods(buf).await?;
+                if methods
+                    .iter()
+                    .any(|method| method == "listBidcosInterfaces")
+                {
+                    return Err(err);
+                }
+                vec![]
+            }
+        };
+        let mut duty_cycles: Vec<DutyCycle> = interfaces
+            .into_iter()
+            .map(|interface| DutyCycle {
+                address: interface.address,
+                description: interface.description,
+                connected: interface.connected,
+                level: interface.duty_cycle.map(f64::from),
+            })
+            .collect();
+
+        let access_points: Vec<DeviceDescription> = self
+            .list_devices(buf)
+            .await?
+            .into_iter()
+            .filter(|dev| interface::HMIP_ACCESS_POINT_TYPES.contains(&dev.ty.as_str()))
+            .collect();
+        for dev in access_points {
+            let address = match dev.maintenance_channel_address() {
+                Some(address) => address.to_string(),
+                None => format!("{}:0", dev.address),
+            };
+            // one unreachable access point must not hide the other interfaces
+            let (connected, level) = match self.get_paramset(&address, "VALUES", buf).await {
+                Ok(paramset) => {
+                    let level = match paramset.get("DUTY_CYCLE_LEVEL") {
+                        Some(Value::Double(level)) => Some(*level),
+                        Some(Value::Int(level)) => Some(f64::from(*level)),
+                        _ => None,
+                    };
+                    (!MaintenanceStatus::from_paramset(&paramset).unreach, level)
+                }
+                Err(err) => {
+                    warn!("Failed to read duty cycle of {}: {}", dev.address, err);
+                    (false, None)
+                }
+            };
+            duty_cycles.push(DutyCycle {
+                address: dev.address,
+                description: dev.ty,
+                connected,
+                level,
+            });
+        }
+
+        Ok(duty_cycles)
+    }
+
+    /// Status of `device` read from its maintenance channel.
+    ///
+    /// `None` if the device has no maintenance channel.
+    pub async fn get_maintenance_status(
+        &self,
+        device: &DeviceDescription,
+        buf: &mut String,
+    ) -> Result<Option<MaintenanceStatus>, XmlRpcError> {
+        let address = match device.maintenance_channel_address() {
+            Some(address) => address,
+            None => return Ok(None),
+        };
+        let paramset = self.get_paramset(address, "VALUES", buf).await?;
+        Ok(Some(MaintenanceStatus::from_paramset(&paramset)))
+    }
+
+    /// Switch the info LED of the LAN gateway `interface_address` on or off.
+    pub async fn set_rflgw_info_led(
+        &self,
+        interface_address: &str,
+        on: bool,
+        buf: &mut String,
+    ) -> Result<(), XmlRpcError> {
+        self.xmlrpc
+            .call("setRFLGWInfoLED", &(interface_address, on), buf)
+            .await
+    }
+
+    /// Virtual team devices (e.g. smoke detector groups).
+    pub async fn list_teams(
+        &self,
+        buf: &mut String,
+    ) -> Result<Vec<DeviceDescription>, XmlRpcError> {
+        self.xmlrpc.call("listTeams", &(), buf).await
+    }
+
+    /// Assign the channel `channel_address` to the team channel `team_address`.
+    ///
+    /// An empty `team_address` removes the channel from its team.
+    pub async fn set_team(
+        &self,
+        channel_address: &str,
+        team_address: &str,
+        buf: &mut String,
+    ) -> Result<(), XmlRpcError> {
+        self.xmlrpc
+            .call("setTeam", &(channel_address, team_address), buf)
+            .await
+    }
+
+    /// Create a new instance of the virtual device type `device_type` (e.g. a team).
+    ///
+    /// Returns the address of the new device.
+    pub async fn add_virtual_device_instance(
+        &self,
+        device_type: &str,
         buf: &mut String,
     ) -> Result<String, XmlRpcError> {
         self.xmlrpc
-            .call("getKeyMissmatchDevice", &(reset,), buf)
+            .call("addVirtualDeviceInstance", &(device_type,), buf)
             .await
     }
 
+    /// Address of a device whose AES key does not match the system key.
+    ///
+    /// With `reset` the reported device is cleared, so the next call reports the next device.
+    pub async fn get_key_mismatch_device(
+        &self,
+        reset: bool,
+        buf: &mut String,
+    ) -> Result<Option<String>, XmlRpcError> {
+        let address: String = self
+            .xmlrpc
+            .call("getKeyMismatchDevice", &(reset,), buf)
+            .await?;
+        Ok(Some(address).filter(|address| !address.is_empty()))
+    }
+
+    #[deprecated(note = "use get_key_mismatch_device")]
+    pub async fn get_key_missmatch_device(
+        &self,
+        reset: bool,
+        buf: &mut String,
+    ) -> Result<String, XmlRpcError> {
+        Ok(self
+            .get_key_mismatch_device(reset, buf)
+            .await?
+            .unwrap_or_default())
+    }
+
+    /// Change the AES system key of the interface and all devices to `passphrase`.
+    pub async fn change_key(&self, passphrase: &str, buf: &mut String) -> Result<(), XmlRpcError> {
+        self.xmlrpc.call("changeKey", &(passphrase,), buf).await
+    }
+
+    /// Set a temporary key to communicate with devices still using the old key `passphrase`.
+    ///
+    /// Devices using the temporary key are switched to the current system key.
     pub async fn set_temp_key(
         &self,
         passphrase: &str,
@@ -0,0 +1,74 @@
+use bitflags::bitflags;
+use serde::{Deserialize, Deserializer};
+
+bitflags! {
+    /// Or-connection of flags when querying links.
+    pub struct GetLinksFlags: i32 {
+        /// Links of grouped channels are also returned for the partner channel.
+        const GROUP = 0x1;
+        /// The paramset of the sender is included.
+        const SENDER_PARAMSET = 0x2;
+        /// The paramset of the receiver is included.
+        const RECEIVER_PARAMSET = 0x4;
+    }
+}
+
+bitflags! {
+    /// Or-connection of flags describing the state of a link.
+    struct LinkFlags: i32 {
+        /// The sender of the link is not known to the interface process.
+        const SENDER_BROKEN = 0x1;
+        /// The receiver of the link is not known to the interface process.
+        const RECEIVER_BROKEN = 0x2;
+    }
+}
+
+fn deserialize_link_flags<'de, D>(deserializer: D) -> Result<LinkFlags, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value: i32 = Deserialize::deserialize(deserializer)?;
+    Ok(LinkFlags::from_bits_truncate(value))
+}
+
+/// Direct link between two channels.
+#[derive(Deserialize, Debug)]
+pub struct Link {
+    /// Address of the sending channel.
+    #[serde(rename = "SENDER")]
+    pub sender: String,
+
+    /// Address of the receiving channel.
+    #[serde(rename = "RECEIVER")]
+    pub receiver: String,
+
+    /// Name of the link.
+    #[serde(rename = "NAME", default)]
+    pub name: String,
+
+    /// Textual description of the link.
+    #[serde(rename = "DESCRIPTION", default)]
+    pub description: String,
+
+    /// Or-connection of flags describing the state of the link.
+    #[serde(rename = "FLAGS", default, deserialize_with = "deserialize_link_flags")]
+    flags: LinkFlags,
+}
+
+impl Default for LinkFlags {
+    fn default() -> Self {
+        LinkFlags::empty()
+    }
+}
+
+impl Link {
+    /// The sender of the link is not known to the interface process.
+    pub fn is_sender_broken(&self) -> bool {
+        self.flags.contains(LinkFlags::SENDER_BROKEN)
+    }
+
+    /// The receiver of the link is not known to the interface process.
+    pub fn is_receiver_broken(&self) -> bool {
+        self.flags.contains(LinkFlags::RECEIVER_BROKEN)
+    }
+}
@@ -0,0 +1,150 @@
+use xrs_xmlrpc::value::Value;
+
+use crate::interface::RSSI_UNKNOWN;
+use crate::Paramset;
+
+/// Channel type of the maintenance channel.
+pub(crate) const MAINTENANCE_CHANNEL_TYPE: &str = "MAINTENANCE";
+
+/// State of a device as reported in the VALUES of its maintenance channel (channel 0).
+///
+/// BidCos and HmIP devices use slightly different keys, missing values are `None`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MaintenanceStatus {
+    /// The device did not respond to the last communication.
+    pub unreach: bool,
+
+    /// The device was unreachable at some point, until acknowledged.
+    pub sticky_unreach: Option<bool>,
+
+    /// The battery is low (LOWBAT or LOW_BAT).
+    pub low_battery: Option<bool>,
+
+    /// Reception strength of the interface at the device in dBm.
+    pub rssi_device: Option<i32>,
+
+    /// Reception strength of the device at the interface in dBm.
+    pub rssi_peer: Option<i32>,
+
+    /// The device reached its duty cycle limit (DUTYCYCLE or DUTY_CYCLE).
+    pub duty_cycle: Option<bool>,
+
+    /// Configuration changes have not been transferred to the device yet.
+    pub config_pending: bool,
+
+    /// Supply voltage in V.
+    pub operating_voltage: Option<f64>,
+}
+
+impl MaintenanceStatus {
+    /// Read the status from the VALUES paramset of a maintenance channel.
+    pub fn from_paramset(paramset: &Paramset) -> Self {
+        let bool_value = |keys: &[&str]| {
+            keys.iter().find_map(|key| match paramset.get(*key) {
+                Some(Value::Bool(value)) => Some(*value),
+                Some(Value::Int(value)) => Some(*value != 0),
+                _ => None,
+            })
+        };
+        let rssi = |key: &str| match paramset.get(key) {
+            Some(Value::Int(value)) if !RSSI_UNKNOWN.contains(value) => Some(*value),
+            _ => None,
+        };
+
+        Self {
+            unreach: bool_value(&["UNREACH"]).unwrap_or_default(),
+            sticky_unreach: bool_value(&["STICKY_UNREACH"]),
+            low_battery: bool_value(&["LOWBAT", "LOW_BAT"]),
+            rssi_device: rssi("RSSI_DEVICE"),
+            rssi_peer: rssi("RSSI_PEER"),
+            duty_cycle: bool_value(&["DUTYCYCLE", "DUTY_CYCLE"]),
+            config_pending: bool_value(&["CONFIG_PENDING"]).unwrap_or_default(),
+            operating_voltage: match paramset.get("OPERATING_VOLTAGE") {
+                Some(Value::Double(value)) => Some(*value),
+                Some(Value::Int(value)) => Some(f64::from(*value)),
+                _ => None,
+            },
+        }
+    }
+
+    /// The device needs attention.
+    pub fn has_problem(&self) -> bool {
+        self.unreach
+            || self.low_battery == Some(true)
+            || self.duty_cycle == Some(true)
+            || self.config_pending
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paramset(values: Vec<(&str, Value<'static>)>) -> Paramset<'static> {
+        values
+            .into_iter()
+            .map(|(key, value)| (key.to_string(), value))
+            .collect()
+    }
+
+    #[test]
+    fn bidcos_status() {
+        let status = MaintenanceStatus::from_paramset(&paramset(vec![
+            ("UNREACH", Value::Bool(false)),
+            ("STICKY_UNREACH", Value::Bool(true)),
+            ("LOWBAT", Value::Bool(true)),
+            ("RSSI_DEVICE", Value::Int(65536)),
+            ("RSSI_PEER", Value::Int(-62)),
+            ("DUTYCYCLE", Value::Bool(false)),
+            ("CONFIG_PENDING", Value::Bool(false)),
+        ]));
+        assert_eq!(
+            status,
+            MaintenanceStatus {
+                unreach: false,
+                sticky_unreach: Some(true),
+                low_battery: Some(true),
+                rssi_device: None,
+                rssi_peer: Some(-62),
+                duty_cycle: Some(false),
+                config_pending: false,
+                operating_voltage: None,
+            }
+        );
+        assert!(status.has_problem());
+    }
+
+    #[test]
+    fn hmip_status() {
+        let status = MaintenanceStatus::from_paramset(&paramset(vec![
+            ("UNREACH", Value::Bool(false)),
+            ("LOW_BAT", Value::Bool(false)),
+            ("RSSI_DEVICE", Value::Int(-71)),
+            ("RSSI_PEER", Value::Int(-65535)),
+            ("DUTY_CYCLE", Value::Bool(false)),
+            ("CONFIG_PENDING", Value::Bool(true)),
+            ("OPERATING_VOLTAGE", Value::Double(2.9)),
+        ]));
+        assert_eq!(
+            status,
+            MaintenanceStatus {
+                unreach: false,
+                sticky_unreach: None,
+                low_battery: Some(false),
+                rssi_device: Some(-71),
+                rssi_peer: None,
+                duty_cycle: Some(false),
+                config_pending: true,
+                operating_voltage: Some(2.9),
+            }
+        );
+        assert!(status.has_problem());
+    }
+
+    #[test]
+    fn empty_status() {
+        let status = MaintenanceStatus::from_paramset(&Paramset::new());
+        assert_eq!(status, MaintenanceStatus::default());
+        assert!(!status.has_problem());
+    }
+}
@@ -0,0 +1,77 @@
+//! Batching of calls with `system.multicall`.
+
+use serde::{Deserialize, Serialize};
+
+/// Single call of a `system.multicall` request.
+#[derive(Serialize)]
+pub(crate) struct MethodCall<'a> {
+    #[serde(rename = "methodName")]
+    pub method_name: &'a str,
+    pub params: Vec<&'a str>,
+}
+
+/// Result of a single call of a `system.multicall` request.
+///
+/// Successful results are wrapped in an array of one element, failed calls are fault structs.
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub(crate) enum MulticallResult<T> {
+    Ok((T,)),
+    Fault {
+        #[serde(rename = "faultCode")]
+        fault_code: i32,
+        #[serde(rename = "faultString")]
+        fault_string: String,
+    },
+}
+
+impl<T> MulticallResult<T> {
+    pub fn into_result(self) -> Result<T, String> {
+        match self {
+            MulticallResult::Ok((value,)) => Ok(value),
+            MulticallResult::Fault {
+                fault_code,
+                fault_string,
+            } => Err(format!("{} ({})", fault_string, fault_code)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    #[test]
+    fn serialize_call() {
+        let call = MethodCall {
+            method_name: "getParamset",
+            params: vec!["LEQ0123456:0", "VALUES"],
+        };
+        assert_eq!(
+            serde_json::to_value(&call).unwrap(),
+            serde_json::json!({"methodName": "getParamset", "params": ["LEQ0123456:0", "VALUES"]})
+        );
+    }
+
+    #[test]
+    fn mixed_results() {
+        let response = serde_json::json!([
+            [{"UNREACH": 0.0, "OPERATING_VOLTAGE": 2.9}],
+            {"faultCode": -2, "faultString": "Unknown instance"},
+            [{}],
+        ]);
+        let results: Vec<Result<HashMap<String, f64>, String>> =
+            Vec::<MulticallResult<HashMap<String, f64>>>::deserialize(response)
+                .unwrap()
+                .into_iter()
+                .map(MulticallResult::into_result)
+                .collect();
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].as_ref().unwrap()["OPERATING_VOLTAGE"], 2.9);
+        assert_eq!(results[1], Err("Unknown instance (-2)".to_string()));
+        assert!(results[2].as_ref().unwrap().is_empty());
+    }
+}
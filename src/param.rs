@@ -28,6 +28,54 @@ pub enum ParameterDescription {
     Action(BoolParameterDescription),
 }
 
+/// Parameter can be read.
+const OPERATION_READ: i32 = 0x1;
+/// Parameter can be written.
+const OPERATION_WRITE: i32 = 0x2;
+/// Parameter changes are reported as events.
+const OPERATION_EVENT: i32 = 0x4;
+
+impl ParameterDescription {
+    /// Bitfield of supported operations.
+    pub fn operations(&self) -> i32 {
+        match self {
+            ParameterDescription::Float(pd) => pd.operations,
+            ParameterDescription::Integer(pd) => pd.operations,
+            ParameterDescription::Bool(pd) => pd.operations,
+            ParameterDescription::Enum(pd) => pd.operations as i32,
+            ParameterDescription::String(pd) => pd.operations,
+            ParameterDescription::Action(pd) => pd.operations,
+        }
+    }
+
+    /// Unit of the parameter value.
+    pub fn unit(&self) -> Option<&str> {
+        match self {
+            ParameterDescription::Float(pd) => pd.unit.as_deref(),
+            ParameterDescription::Integer(pd) => pd.unit.as_deref(),
+            ParameterDescription::Bool(pd) => pd.unit.as_deref(),
+            ParameterDescription::Enum(pd) => pd.unit.as_deref(),
+            ParameterDescription::String(pd) => pd.unit.as_deref(),
+            ParameterDescription::Action(pd) => pd.unit.as_deref(),
+        }
+    }
+
+    /// Parameter can be read.
+    pub fn is_readable(&self) -> bool {
+        self.operations() & OPERATION_READ != 0
+    }
+
+    /// Parameter can be written.
+    pub fn is_writable(&self) -> bool {
+        self.operations() & OPERATION_WRITE != 0
+    }
+
+    /// Parameter changes are reported as events.
+    pub fn is_event(&self) -> bool {
+        self.operations() & OPERATION_EVENT != 0
+    }
+}
+
 #[derive(Deserialize)]
 pub struct SpecialF32 {
     #[serde(rename = "ID")]
@@ -149,14 +197,17 @@ pub struct EnumParameterDescription {
     #[serde(rename = "FLAGS")]
     pub flags: u8,
 
+    /// Index of the default value.
     #[serde(rename = "DEFAULT")]
-    pub default: String,
+    pub default: i32,
 
+    /// Lowest index.
     #[serde(rename = "MIN")]
-    pub min: String,
+    pub min: i32,
 
+    /// Highest index.
     #[serde(rename = "MAX")]
-    pub max: String,
+    pub max: i32,
 
     #[serde(rename = "UNIT")]
     pub unit: Option<String>,
@@ -167,10 +218,30 @@ pub struct EnumParameterDescription {
     #[serde(rename = "CONTROL")]
     pub control: Option<String>,
 
+    /// Labels by index, unused indices have an empty label.
     #[serde(rename = "VALUE_LIST")]
     pub values: Vec<String>,
 }
 
+impl EnumParameterDescription {
+    /// Label of the value with the given index.
+    pub fn label(&self, index: i32) -> Option<&str> {
+        usize::try_from(index)
+            .ok()
+            .and_then(|index| self.values.get(index))
+            .map(|label| label.as_str())
+            .filter(|label| !label.is_empty())
+    }
+
+    /// Index of the value with the given label.
+    pub fn index(&self, label: &str) -> Option<i32> {
+        self.values
+            .iter()
+            .position(|value| !value.is_empty() && value == label)
+            .map(|index| index as i32)
+    }
+}
+
 #[derive(Deserialize)]
 pub struct StringParameterDescription {
     // Bitfield: 1=Read, 2=Write, 4=Event
@@ -199,3 +270,72 @@ pub struct StringParameterDescription {
     #[serde(rename = "CONTROL")]
     pub control: Option<String>,
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn description(description: serde_json::Value) -> ParameterDescription {
+        serde_json::from_value(description).unwrap()
+    }
+
+    #[test]
+    fn enum_description() {
+        let pd = description(json!({
+            "TYPE": "ENUM", "OPERATIONS": 7, "FLAGS": 1,
+            "DEFAULT": 0, "MIN": 0, "MAX": 3, "UNIT": "", "TAB_ORDER": 2,
+            "VALUE_LIST": ["AUTO-MODE", "MANU-MODE", "", "BOOST-MODE"],
+        }));
+        let pd = match pd {
+            ParameterDescription::Enum(pd) => pd,
+            _ => panic!("expected an enum description"),
+        };
+        assert_eq!(pd.default, 0);
+        assert_eq!(pd.min, 0);
+        assert_eq!(pd.max, 3);
+        assert_eq!(pd.tab_order, Some(2));
+        assert_eq!(pd.values.len(), 4);
+
+        assert_eq!(pd.label(1), Some("MANU-MODE"));
+        assert_eq!(pd.label(2), None);
+        assert_eq!(pd.label(4), None);
+        assert_eq!(pd.label(-1), None);
+        assert_eq!(pd.index("BOOST-MODE"), Some(3));
+        assert_eq!(pd.index(""), None);
+        assert_eq!(pd.index("PARTY-MODE"), None);
+    }
+
+    #[test]
+    fn operations() {
+        let level = description(json!({
+            "TYPE": "FLOAT", "OPERATIONS": 7, "FLAGS": 1,
+            "DEFAULT": 0.0, "MIN": 0.0, "MAX": 1.0, "UNIT": "100%",
+        }));
+        assert_eq!(level.operations(), 7);
+        assert_eq!(level.unit(), Some("100%"));
+        assert!(level.is_readable());
+        assert!(level.is_writable());
+        assert!(level.is_event());
+
+        let press = description(json!({
+            "TYPE": "ACTION", "OPERATIONS": 6, "FLAGS": 1,
+            "DEFAULT": false, "MIN": false, "MAX": true,
+        }));
+        assert_eq!(press.unit(), None);
+        assert!(!press.is_readable());
+        assert!(press.is_writable());
+        assert!(press.is_event());
+
+        let mode = description(json!({
+            "TYPE": "ENUM", "OPERATIONS": 5, "FLAGS": 1,
+            "DEFAULT": 0, "MIN": 0, "MAX": 1, "UNIT": "",
+            "VALUE_LIST": ["AUTO", "MANUAL"],
+        }));
+        assert_eq!(mode.operations(), 5);
+        assert!(mode.is_readable());
+        assert!(!mode.is_writable());
+        assert!(mode.is_event());
+    }
+}
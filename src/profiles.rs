@@ -0,0 +1,642 @@
+//! Typed wrappers for common actuator channels.
+//!
+//! The wrappers are recognized from the channel type of the [`DeviceDescription`] and hide the
+//! differences between BidCos and HmIP devices.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::time::Duration;
+
+use xrs_xmlrpc::value::Value;
+use xrs_xmlrpc::XmlRpcError;
+
+use crate::{DeviceDescription, HomeMaticClient, Paramset};
+
+/// Error of a profile operation.
+#[derive(Debug)]
+pub enum ProfileError {
+    /// The XML-RPC call failed.
+    XmlRpc(XmlRpcError),
+
+    /// The device reported a value of an unexpected type.
+    UnexpectedValue { address: String, value_key: String },
+
+    /// The operation is not supported by the channel.
+    Unsupported(&'static str),
+}
+
+impl fmt::Display for ProfileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProfileError::XmlRpc(err) => err.fmt(f),
+            ProfileError::UnexpectedValue { address, value_key } => {
+                write!(f, "unexpected value type of {} on {}", value_key, address)
+            }
+            ProfileError::Unsupported(operation) => write!(f, "{} is not supported", operation),
+        }
+    }
+}
+
+impl std::error::Error for ProfileError {}
+
+impl From<XmlRpcError> for ProfileError {
+    fn from(err: XmlRpcError) -> Self {
+        ProfileError::XmlRpc(err)
+    }
+}
+
+/// Radio protocol family of a channel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Variant {
+    BidCos,
+    HmIP,
+}
+
+/// Actuator channel with a typed wrapper.
+pub enum DeviceProfile<'a> {
+    Switch(Switch<'a>),
+    Dimmer(Dimmer<'a>),
+    Blind(Blind<'a>),
+    Thermostat(Thermostat<'a>),
+}
+
+impl<'a> DeviceProfile<'a> {
+    /// Recognize the profile of a channel from its channel type.
+    pub fn from_channel(client: &'a HomeMaticClient, channel: &DeviceDescription) -> Option<Self> {
+        let address = channel.address.clone();
+        let ty = channel.ty.as_str();
+        if Switch::CHANNEL_TYPES.contains(&ty) {
+            Some(DeviceProfile::Switch(Switch::new(client, address)))
+        } else if Dimmer::CHANNEL_TYPES.contains(&ty) {
+            Some(DeviceProfile::Dimmer(Dimmer::new(client, address)))
+        } else if Blind::CHANNEL_TYPES.contains(&ty) {
+            Some(DeviceProfile::Blind(Blind::new(client, address)))
+        } else if Thermostat::BIDCOS_CHANNEL_TYPES.contains(&ty) {
+            Some(DeviceProfile::Thermostat(Thermostat::new(
+                client,
+                address,
+                Variant::BidCos,
+            )))
+        } else if Thermostat::HMIP_CHANNEL_TYPES.contains(&ty) {
+            Some(DeviceProfile::Thermostat(Thermostat::new(
+                client,
+                address,
+                Variant::HmIP,
+            )))
+        } else {
+            None
+        }
+    }
+
+    /// Profiles of all channels of `device`.
+    ///
+    /// `channels` are the descriptions of the device channels, e.g. from `list_devices`.
+    pub fn from_device(
+        client: &'a HomeMaticClient,
+        device: &DeviceDescription,
+        channels: &[DeviceDescription],
+    ) -> Vec<Self> {
+        channels
+            .iter()
+            .filter(|channel| channel.parent == device.address)
+            .filter_map(|channel| Self::from_channel(client, channel))
+            .collect()
+    }
+
+    /// Address of the channel.
+    pub fn address(&self) -> &str {
+        match self {
+            DeviceProfile::Switch(profile) => &profile.address,
+            DeviceProfile::Dimmer(profile) => &profile.address,
+            DeviceProfile::Blind(profile) => &profile.address,
+            DeviceProfile::Thermostat(profile) => &profile.address,
+        }
+    }
+}
+
+fn bool_value(address: &str, value_key: &str, value: Option<&Value>) -> Result<bool, ProfileError> {
+    match value {
+        Some(Value::Bool(value)) => Ok(*value),
+        Some(Value::Int(value)) => Ok(*value != 0),
+        _ => Err(unexpected_value(address, value_key)),
+    }
+}
+
+fn f64_value(address: &str, value_key: &str, value: Option<&Value>) -> Result<f64, ProfileError> {
+    match value {
+        Some(Value::Double(value)) => Ok(*value),
+        Some(Value::Int(value)) => Ok(f64::from(*value)),
+        _ => Err(unexpected_value(address, value_key)),
+    }
+}
+
+fn i32_value(address: &str, value_key: &str, value: Option<&Value>) -> Result<i32, ProfileError> {
+    match value {
+        Some(Value::Int(value)) => Ok(*value),
+        _ => Err(unexpected_value(address, value_key)),
+    }
+}
+
+async fn get_bool(
+    client: &HomeMaticClient,
+    address: &str,
+    value_key: &str,
+) -> Result<bool, ProfileError> {
+    let mut buf = String::new();
+    let value = client.get_value(address, value_key, &mut buf).await?;
+    bool_value(address, value_key, Some(&value))
+}
+
+async fn get_f64(
+    client: &HomeMaticClient,
+    address: &str,
+    value_key: &str,
+) -> Result<f64, ProfileError> {
+    let mut buf = String::new();
+    let value = client.get_value(address, value_key, &mut buf).await?;
+    f64_value(address, value_key, Some(&value))
+}
+
+async fn get_i32(
+    client: &HomeMaticClient,
+    address: &str,
+    value_key: &str,
+) -> Result<i32, ProfileError> {
+    let mut buf = String::new();
+    let value = client.get_value(address, value_key, &mut buf).await?;
+    i32_value(address, value_key, Some(&value))
+}
+
+async fn get_values<'a>(
+    client: &HomeMaticClient,
+    address: &str,
+    buf: &'a mut String,
+) -> Result<Paramset<'a>, ProfileError> {
+    Ok(client.get_paramset(address, "VALUES", buf).await?)
+}
+
+async fn set(
+    client: &HomeMaticClient,
+    address: &str,
+    value_key: &str,
+    value: Value<'_>,
+) -> Result<(), ProfileError> {
+    let mut buf = String::new();
+    client
+        .set_value(address, value_key, value, &mut buf)
+        .await?;
+    Ok(())
+}
+
+fn unexpected_value(address: &str, value_key: &str) -> ProfileError {
+    ProfileError::UnexpectedValue {
+        address: address.to_string(),
+        value_key: value_key.to_string(),
+    }
+}
+
+/// Switch actuator channel (`STATE`).
+pub struct Switch<'a> {
+    client: &'a HomeMaticClient,
+    address: String,
+}
+
+impl<'a> Switch<'a> {
+    pub const CHANNEL_TYPES: &'static [&'static str] = &["SWITCH", "SWITCH_VIRTUAL_RECEIVER"];
+
+    pub fn new(client: &'a HomeMaticClient, address: impl Into<String>) -> Self {
+        Self {
+            client,
+            address: address.into(),
+        }
+    }
+
+    pub async fn state(&self) -> Result<bool, ProfileError> {
+        get_bool(self.client, &self.address, "STATE").await
+    }
+
+    pub async fn set_state(&self, on: bool) -> Result<(), ProfileError> {
+        set(self.client, &self.address, "STATE", Value::Bool(on)).await
+    }
+
+    /// Read all values of the channel at once.
+    pub async fn values(&self) -> Result<SwitchValues, ProfileError> {
+        let mut buf = String::new();
+        let values = get_values(self.client, &self.address, &mut buf).await?;
+        SwitchValues::from_paramset(&self.address, &values)
+    }
+}
+
+/// Values of a switch channel.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SwitchValues {
+    pub state: bool,
+}
+
+impl SwitchValues {
+    /// Read the values from the VALUES paramset of the channel `address`.
+    pub fn from_paramset(address: &str, values: &Paramset) -> Result<Self, ProfileError> {
+        Ok(Self {
+            state: bool_value(address, "STATE", values.get("STATE"))?,
+        })
+    }
+}
+
+/// Dimmer actuator channel (`LEVEL` from 0.0 to 1.0).
+pub struct Dimmer<'a> {
+    client: &'a HomeMaticClient,
+    address: String,
+}
+
+impl<'a> Dimmer<'a> {
+    pub const CHANNEL_TYPES: &'static [&'static str] =
+        &["DIMMER", "VIRTUAL_DIMMER", "DIMMER_VIRTUAL_RECEIVER"];
+
+    pub fn new(client: &'a HomeMaticClient, address: impl Into<String>) -> Self {
+        Self {
+            client,
+            address: address.into(),
+        }
+    }
+
+    pub async fn level(&self) -> Result<f64, ProfileError> {
+        get_f64(self.client, &self.address, "LEVEL").await
+    }
+
+    pub async fn set_level(&self, level: f64) -> Result<(), ProfileError> {
+        set(self.client, &self.address, "LEVEL", Value::Double(level)).await
+    }
+
+    /// Dim to `level` within `ramp_time`.
+    pub async fn set_level_with_ramp(
+        &self,
+        level: f64,
+        ramp_time: Duration,
+    ) -> Result<(), ProfileError> {
+        let mut values = HashMap::new();
+        values.insert(
+            "RAMP_TIME".to_string(),
+            Value::Double(ramp_time.as_secs_f64()),
+        );
+        values.insert("LEVEL".to_string(), Value::Double(level));
+
+        let mut buf = String::new();
+        self.client
+            .put_paramset(&self.address, "VALUES", values, &mut buf)
+            .await?;
+        Ok(())
+    }
+
+    /// Read all values of the channel at once.
+    pub async fn values(&self) -> Result<LevelValues, ProfileError> {
+        let mut buf = String::new();
+        let values = get_values(self.client, &self.address, &mut buf).await?;
+        LevelValues::from_paramset(&self.address, &values)
+    }
+}
+
+/// Values of a dimmer or blind channel.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LevelValues {
+    /// Level from 0.0 to 1.0.
+    pub level: f64,
+}
+
+impl LevelValues {
+    /// Read the values from the VALUES paramset of the channel `address`.
+    pub fn from_paramset(address: &str, values: &Paramset) -> Result<Self, ProfileError> {
+        Ok(Self {
+            level: f64_value(address, "LEVEL", values.get("LEVEL"))?,
+        })
+    }
+}
+
+/// Blind or shutter actuator channel (`LEVEL` from 0.0 closed to 1.0 open).
+pub struct Blind<'a> {
+    client: &'a HomeMaticClient,
+    address: String,
+}
+
+impl<'a> Blind<'a> {
+    pub const CHANNEL_TYPES: &'static [&'static str] = &[
+        "BLIND",
+        "JALOUSIE",
+        "BLIND_VIRTUAL_RECEIVER",
+        "SHUTTER_VIRTUAL_RECEIVER",
+    ];
+
+    pub fn new(client: &'a HomeMaticClient, address: impl Into<String>) -> Self {
+        Self {
+            client,
+            address: address.into(),
+        }
+    }
+
+    pub async fn level(&self) -> Result<f64, ProfileError> {
+        get_f64(self.client, &self.address, "LEVEL").await
+    }
+
+    pub async fn set_level(&self, level: f64) -> Result<(), ProfileError> {
+        set(self.client, &self.address, "LEVEL", Value::Double(level)).await
+    }
+
+    /// Stop a running movement.
+    pub async fn stop(&self) -> Result<(), ProfileError> {
+        set(self.client, &self.address, "STOP", Value::Bool(true)).await
+    }
+
+    /// Read all values of the channel at once.
+    pub async fn values(&self) -> Result<LevelValues, ProfileError> {
+        let mut buf = String::new();
+        let values = get_values(self.client, &self.address, &mut buf).await?;
+        LevelValues::from_paramset(&self.address, &values)
+    }
+}
+
+/// Operating mode of a thermostat.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ControlMode {
+    Auto,
+    Manual,
+    Party,
+    Boost,
+}
+
+impl ControlMode {
+    /// Mode of a BidCos `CONTROL_MODE` index.
+    fn from_bidcos(index: i32) -> Option<Self> {
+        match index {
+            0 => Some(ControlMode::Auto),
+            1 => Some(ControlMode::Manual),
+            2 => Some(ControlMode::Party),
+            3 => Some(ControlMode::Boost),
+            _ => None,
+        }
+    }
+
+    /// Mode of a HmIP `SET_POINT_MODE` index and `BOOST_MODE`.
+    ///
+    /// The HmIP value list only has AUTOMATIC, MANUAL and PARTY, boost is a separate parameter.
+    fn from_hmip(index: i32, boost: bool) -> Option<Self> {
+        match index {
+            0..=2 if boost => Some(ControlMode::Boost),
+            0 => Some(ControlMode::Auto),
+            1 => Some(ControlMode::Manual),
+            2 => Some(ControlMode::Party),
+            _ => None,
+        }
+    }
+}
+
+/// Heating thermostat channel.
+///
+/// BidCos thermostats use `SET_TEMPERATURE` and `CONTROL_MODE`, HmIP thermostats use
+/// `SET_POINT_TEMPERATURE`, `SET_POINT_MODE` and `BOOST_MODE`.
+pub struct Thermostat<'a> {
+    client: &'a HomeMaticClient,
+    address: String,
+    variant: Variant,
+}
+
+impl<'a> Thermostat<'a> {
+    pub const BIDCOS_CHANNEL_TYPES: &'static [&'static str] =
+        &["CLIMATECONTROL_RT_TRANSCEIVER", "THERMALCONTROL_TRANSMIT"];
+
+    pub const HMIP_CHANNEL_TYPES: &'static [&'static str] = &["HEATING_CLIMATECONTROL_TRANSCEIVER"];
+
+    pub fn new(client: &'a HomeMaticClient, address: impl Into<String>, variant: Variant) -> Self {
+        Self {
+            client,
+            address: address.into(),
+            variant,
+        }
+    }
+
+    pub fn variant(&self) -> Variant {
+        self.variant
+    }
+
+    /// Measured temperature in °C.
+    pub async fn actual_temperature(&self) -> Result<f64, ProfileError> {
+        get_f64(self.client, &self.address, "ACTUAL_TEMPERATURE").await
+    }
+
+    /// Target temperature in °C.
+    pub async fn set_point(&self) -> Result<f64, ProfileError> {
+        get_f64(self.client, &self.address, self.set_point_key()).await
+    }
+
+    pub async fn set_set_point(&self, temperature: f64) -> Result<(), ProfileError> {
+        set(
+            self.client,
+            &self.address,
+            self.set_point_key(),
+            Value::Double(temperature),
+        )
+        .await
+    }
+
+    pub async fn control_mode(&self) -> Result<ControlMode, ProfileError> {
+        match self.variant {
+            Variant::BidCos => {
+                let index = get_i32(self.client, &self.address, "CONTROL_MODE").await?;
+                ControlMode::from_bidcos(index)
+                    .ok_or_else(|| unexpected_value(&self.address, "CONTROL_MODE"))
+            }
+            Variant::HmIP => {
+                let index = get_i32(self.client, &self.address, "SET_POINT_MODE").await?;
+                let boost = get_bool(self.client, &self.address, "BOOST_MODE").await?;
+                ControlMode::from_hmip(index, boost)
+                    .ok_or_else(|| unexpected_value(&self.address, "SET_POINT_MODE"))
+            }
+        }
+    }
+
+    /// Switch the operating mode.
+    ///
+    /// Manual mode keeps the current target temperature. Party mode cannot be set this way.
+    pub async fn set_control_mode(&self, mode: ControlMode) -> Result<(), ProfileError> {
+        match (self.variant, mode) {
+            (_, ControlMode::Boost) => {
+                set(self.client, &self.address, "BOOST_MODE", Value::Bool(true)).await
+            }
+            (Variant::BidCos, ControlMode::Auto) => {
+                set(self.client, &self.address, "AUTO_MODE", Value::Bool(true)).await
+            }
+            (Variant::BidCos, ControlMode::Manual) => {
+                let temperature = self.set_point().await?;
+                set(
+                    self.client,
+                    &self.address,
+                    "MANU_MODE",
+                    Value::Double(temperature),
+                )
+                .await
+            }
+            (Variant::HmIP, ControlMode::Auto) => {
+                set(self.client, &self.address, "CONTROL_MODE", Value::Int(0)).await
+            }
+            (Variant::HmIP, ControlMode::Manual) => {
+                set(self.client, &self.address, "CONTROL_MODE", Value::Int(1)).await
+            }
+            (_, ControlMode::Party) => Err(ProfileError::Unsupported("party mode")),
+        }
+    }
+
+    /// Read all values of the channel at once.
+    pub async fn values(&self) -> Result<ThermostatValues, ProfileError> {
+        let mut buf = String::new();
+        let values = get_values(self.client, &self.address, &mut buf).await?;
+        ThermostatValues::from_paramset(&self.address, self.variant, &values)
+    }
+
+    fn set_point_key(&self) -> &'static str {
+        set_point_key(self.variant)
+    }
+}
+
+fn set_point_key(variant: Variant) -> &'static str {
+    match variant {
+        Variant::BidCos => "SET_TEMPERATURE",
+        Variant::HmIP => "SET_POINT_TEMPERATURE",
+    }
+}
+
+/// Values of a thermostat channel.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThermostatValues {
+    /// Measured temperature in °C.
+    pub actual_temperature: f64,
+
+    /// Target temperature in °C.
+    pub set_point: f64,
+
+    pub control_mode: ControlMode,
+}
+
+impl ThermostatValues {
+    /// Read the values from the VALUES paramset of the channel `address`.
+    pub fn from_paramset(
+        address: &str,
+        variant: Variant,
+        values: &Paramset,
+    ) -> Result<Self, ProfileError> {
+        let control_mode = match variant {
+            Variant::BidCos => {
+                let index = i32_value(address, "CONTROL_MODE", values.get("CONTROL_MODE"))?;
+                ControlMode::from_bidcos(index)
+                    .ok_or_else(|| unexpected_value(address, "CONTROL_MODE"))?
+            }
+            Variant::HmIP => {
+                let index = i32_value(address, "SET_POINT_MODE", values.get("SET_POINT_MODE"))?;
+                let boost = bool_value(address, "BOOST_MODE", values.get("BOOST_MODE"))?;
+                ControlMode::from_hmip(index, boost)
+                    .ok_or_else(|| unexpected_value(address, "SET_POINT_MODE"))?
+            }
+        };
+        let set_point_key = set_point_key(variant);
+        Ok(Self {
+            actual_temperature: f64_value(
+                address,
+                "ACTUAL_TEMPERATURE",
+                values.get("ACTUAL_TEMPERATURE"),
+            )?,
+            set_point: f64_value(address, set_point_key, values.get(set_point_key))?,
+            control_mode,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paramset(values: Vec<(&str, Value<'static>)>) -> Paramset<'static> {
+        values
+            .into_iter()
+            .map(|(key, value)| (key.to_string(), value))
+            .collect()
+    }
+
+    #[test]
+    fn switch_values() {
+        let values = paramset(vec![
+            ("STATE", Value::Bool(true)),
+            ("WORKING", Value::Bool(false)),
+        ]);
+        assert_eq!(
+            SwitchValues::from_paramset("ABC0000001:1", &values).unwrap(),
+            SwitchValues { state: true }
+        );
+
+        let values = paramset(vec![("STATE", Value::Double(1.0))]);
+        assert!(matches!(
+            SwitchValues::from_paramset("ABC0000001:1", &values),
+            Err(ProfileError::UnexpectedValue { .. })
+        ));
+    }
+
+    #[test]
+    fn level_values() {
+        let values = paramset(vec![
+            ("LEVEL", Value::Double(0.35)),
+            ("WORKING", Value::Bool(false)),
+        ]);
+        assert_eq!(
+            LevelValues::from_paramset("ABC0000002:1", &values).unwrap(),
+            LevelValues { level: 0.35 }
+        );
+
+        let values = paramset(vec![("STOP", Value::Bool(false))]);
+        assert!(LevelValues::from_paramset("ABC0000002:1", &values).is_err());
+    }
+
+    #[test]
+    fn bidcos_thermostat_values() {
+        let values = paramset(vec![
+            ("ACTUAL_TEMPERATURE", Value::Double(20.5)),
+            ("SET_TEMPERATURE", Value::Double(21.0)),
+            ("CONTROL_MODE", Value::Int(3)),
+            ("BOOST_STATE", Value::Int(5)),
+        ]);
+        assert_eq!(
+            ThermostatValues::from_paramset("ABC0000003:4", Variant::BidCos, &values).unwrap(),
+            ThermostatValues {
+                actual_temperature: 20.5,
+                set_point: 21.0,
+                control_mode: ControlMode::Boost,
+            }
+        );
+    }
+
+    #[test]
+    fn hmip_thermostat_values() {
+        let mut values = paramset(vec![
+            ("ACTUAL_TEMPERATURE", Value::Double(19.8)),
+            ("SET_POINT_TEMPERATURE", Value::Double(17.0)),
+            ("SET_POINT_MODE", Value::Int(1)),
+            ("BOOST_MODE", Value::Bool(false)),
+        ]);
+        assert_eq!(
+            ThermostatValues::from_paramset("000A1BE9A7B2C3:1", Variant::HmIP, &values).unwrap(),
+            ThermostatValues {
+                actual_temperature: 19.8,
+                set_point: 17.0,
+                control_mode: ControlMode::Manual,
+            }
+        );
+
+        values.insert("SET_POINT_MODE".to_string(), Value::Int(0));
+        values.insert("BOOST_MODE".to_string(), Value::Bool(true));
+        let thermostat =
+            ThermostatValues::from_paramset("000A1BE9A7B2C3:1", Variant::HmIP, &values).unwrap();
+        assert_eq!(thermostat.control_mode, ControlMode::Boost);
+
+        // Index 3 is not part of the HmIP value list.
+        values.insert("SET_POINT_MODE".to_string(), Value::Int(3));
+        values.insert("BOOST_MODE".to_string(), Value::Bool(false));
+        assert!(
+            ThermostatValues::from_paramset("000A1BE9A7B2C3:1", Variant::HmIP, &values).is_err()
+        );
+    }
+}
@@ -0,0 +1,442 @@
+//! Week programs of heating thermostats.
+//!
+//! A week program is stored in the MASTER paramset as one pair of `TEMPERATURE_<DAY>_<N>` and
+//! `ENDTIME_<DAY>_<N>` parameters per time slot (BidCos). HmIP thermostats support several
+//! programs, their parameters are prefixed with `P<PROGRAM>_`.
+
+use std::fmt;
+
+use xrs_xmlrpc::value::Value;
+
+use crate::Paramset;
+
+/// Minutes of a day, the end time of the last slot of each day.
+pub const MINUTES_PER_DAY: u16 = 24 * 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Weekday {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+impl Weekday {
+    pub const ALL: [Weekday; 7] = [
+        Weekday::Monday,
+        Weekday::Tuesday,
+        Weekday::Wednesday,
+        Weekday::Thursday,
+        Weekday::Friday,
+        Weekday::Saturday,
+        Weekday::Sunday,
+    ];
+
+    /// Name of the day as used in parameter keys.
+    pub fn key(self) -> &'static str {
+        match self {
+            Weekday::Monday => "MONDAY",
+            Weekday::Tuesday => "TUESDAY",
+            Weekday::Wednesday => "WEDNESDAY",
+            Weekday::Thursday => "THURSDAY",
+            Weekday::Friday => "FRIDAY",
+            Weekday::Saturday => "SATURDAY",
+            Weekday::Sunday => "SUNDAY",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|day| day.key().eq_ignore_ascii_case(key))
+    }
+}
+
+impl fmt::Display for Weekday {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.key())
+    }
+}
+
+/// Parameter layout of a week program.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScheduleFormat {
+    /// `TEMPERATURE_<DAY>_<N>` with 24 slots per day.
+    BidCos,
+    /// `P<PROGRAM>_TEMPERATURE_<DAY>_<N>` with 13 slots per day.
+    HmIP { program: u8 },
+}
+
+impl ScheduleFormat {
+    /// Detect the layout from the keys of a MASTER paramset.
+    ///
+    /// For HmIP the given `program` is used.
+    pub fn detect(paramset: &Paramset, program: u8) -> Option<Self> {
+        let hmip = ScheduleFormat::HmIP { program };
+        if paramset.contains_key(&hmip.temperature_key(Weekday::Monday, 1)) {
+            Some(hmip)
+        } else if paramset.contains_key(&ScheduleFormat::BidCos.temperature_key(Weekday::Monday, 1))
+        {
+            Some(ScheduleFormat::BidCos)
+        } else {
+            None
+        }
+    }
+
+    /// Maximum number of slots per day.
+    pub fn max_slots(self) -> usize {
+        match self {
+            ScheduleFormat::BidCos => 24,
+            ScheduleFormat::HmIP { .. } => 13,
+        }
+    }
+
+    /// Lowest and highest temperature of a slot in °C.
+    ///
+    /// BidCos thermostats accept 4.5 (off) and 30.5 (on) in addition to 5.0 to 30.0.
+    pub fn temperature_range(self) -> (f64, f64) {
+        match self {
+            ScheduleFormat::BidCos => (4.5, 30.5),
+            ScheduleFormat::HmIP { .. } => (5.0, 30.0),
+        }
+    }
+
+    pub fn temperature_key(self, day: Weekday, slot: usize) -> String {
+        format!("{}TEMPERATURE_{}_{}", self.prefix(), day.key(), slot)
+    }
+
+    pub fn end_time_key(self, day: Weekday, slot: usize) -> String {
+        format!("{}ENDTIME_{}_{}", self.prefix(), day.key(), slot)
+    }
+
+    fn prefix(self) -> String {
+        match self {
+            ScheduleFormat::BidCos => String::new(),
+            ScheduleFormat::HmIP { program } => format!("P{}_", program),
+        }
+    }
+}
+
+/// Time slot of a day, lasting from the end of the previous slot until `end_time`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Slot {
+    /// End of the slot in minutes since midnight.
+    pub end_time: u16,
+
+    /// Target temperature in °C.
+    pub temperature: f64,
+}
+
+/// Error of parsing or validating a week program.
+#[derive(Debug)]
+pub enum ScheduleError {
+    /// The paramset has no parameter with this key.
+    MissingKey(String),
+
+    /// The parameter has a value of an unexpected type.
+    UnexpectedValue(String),
+
+    /// A slot violates the constraints of the thermostat.
+    InvalidSlot {
+        day: Weekday,
+        slot: usize,
+        reason: &'static str,
+    },
+}
+
+impl fmt::Display for ScheduleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScheduleError::MissingKey(key) => write!(f, "missing parameter {}", key),
+            ScheduleError::UnexpectedValue(key) => write!(f, "unexpected value type of {}", key),
+            ScheduleError::InvalidSlot { day, slot, reason } => {
+                write!(f, "slot {} on {}: {}", slot, day, reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ScheduleError {}
+
+/// Week program of a thermostat.
+///
+/// Each day is a list of slots with increasing end times, the last slot ends at midnight.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeekSchedule {
+    days: [Vec<Slot>; 7],
+}
+
+impl WeekSchedule {
+    /// Schedule with the same slots on every day.
+    pub fn uniform(slots: Vec<Slot>) -> Self {
+        Self {
+            days: std::array::from_fn(|_| slots.clone()),
+        }
+    }
+
+    /// Read the week program from a MASTER paramset.
+    ///
+    /// Slots after the first slot ending at midnight are unused and skipped.
+    pub fn from_paramset(
+        paramset: &Paramset,
+        format: ScheduleFormat,
+    ) -> Result<Self, ScheduleError> {
+        let mut days: [Vec<Slot>; 7] = Default::default();
+        for (day, slots) in Weekday::ALL.into_iter().zip(days.iter_mut()) {
+            for slot in 1..=format.max_slots() {
+                let end_time = get_int(paramset, &format.end_time_key(day, slot))?;
+                let temperature = get_double(paramset, &format.temperature_key(day, slot))?;
+                let end_time = u16::try_from(end_time)
+                    .map_err(|_| ScheduleError::UnexpectedValue(format.end_time_key(day, slot)))?;
+                slots.push(Slot {
+                    end_time,
+                    temperature,
+                });
+                if end_time >= MINUTES_PER_DAY {
+                    break;
+                }
+            }
+        }
+        Ok(Self { days })
+    }
+
+    /// Write the week program to a paramset suitable for `put_paramset`.
+    ///
+    /// Unused slots are filled with the last slot of the day.
+    pub fn to_paramset(&self, format: ScheduleFormat) -> Paramset<'static> {
+        let mut paramset = Paramset::new();
+        for day in Weekday::ALL {
+            let slots = self.day(day);
+            for slot in 1..=format.max_slots() {
+                let value = match slots.get(slot - 1).or_else(|| slots.last()) {
+                    Some(value) => value,
+                    None => break,
+                };
+                paramset.insert(
+                    format.end_time_key(day, slot),
+                    Value::Int(i32::from(value.end_time)),
+                );
+                paramset.insert(
+                    format.temperature_key(day, slot),
+                    Value::Double(value.temperature),
+                );
+            }
+        }
+        paramset
+    }
+
+    /// Check that every day fits into the slots of `format`.
+    ///
+    /// End times must be multiples of 5 minutes and increasing, the last slot must end at midnight
+    /// and temperatures must be multiples of 0.5 °C within
+    /// [`temperature_range`](ScheduleFormat::temperature_range).
+    pub fn validate(&self, format: ScheduleFormat) -> Result<(), ScheduleError> {
+        let (min_temperature, max_temperature) = format.temperature_range();
+        for day in Weekday::ALL {
+            let slots = self.day(day);
+            let invalid = |slot: usize, reason| ScheduleError::InvalidSlot { day, slot, reason };
+
+            if slots.is_empty() {
+                return Err(invalid(1, "day has no slots"));
+            }
+            if slots.len() > format.max_slots() {
+                return Err(invalid(slots.len(), "too many slots"));
+            }
+
+            let mut previous = 0;
+            for (index, slot) in slots.iter().enumerate() {
+                let number = index + 1;
+                if slot.end_time % 5 != 0 {
+                    return Err(invalid(number, "end time is not a multiple of 5 minutes"));
+                }
+                if slot.end_time <= previous {
+                    return Err(invalid(number, "end time is not after the previous slot"));
+                }
+                if slot.end_time > MINUTES_PER_DAY {
+                    return Err(invalid(number, "end time is after midnight"));
+                }
+                if !(min_temperature..=max_temperature).contains(&slot.temperature) {
+                    return Err(invalid(number, "temperature is out of range"));
+                }
+                if (slot.temperature * 2.0).fract() != 0.0 {
+                    return Err(invalid(number, "temperature is not a multiple of 0.5 °C"));
+                }
+                previous = slot.end_time;
+            }
+
+            if previous != MINUTES_PER_DAY {
+                return Err(invalid(slots.len(), "last slot does not end at midnight"));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn day(&self, day: Weekday) -> &[Slot] {
+        &self.days[day as usize]
+    }
+
+    pub fn set_day(&mut self, day: Weekday, slots: Vec<Slot>) {
+        self.days[day as usize] = slots;
+    }
+}
+
+fn get_int(paramset: &Paramset, key: &str) -> Result<i32, ScheduleError> {
+    match paramset.get(key) {
+        Some(Value::Int(value)) => Ok(*value),
+        Some(_) => Err(ScheduleError::UnexpectedValue(key.to_string())),
+        None => Err(ScheduleError::MissingKey(key.to_string())),
+    }
+}
+
+fn get_double(paramset: &Paramset, key: &str) -> Result<f64, ScheduleError> {
+    match paramset.get(key) {
+        Some(Value::Double(value)) => Ok(*value),
+        Some(Value::Int(value)) => Ok(f64::from(*value)),
+        Some(_) => Err(ScheduleError::UnexpectedValue(key.to_string())),
+        None => Err(ScheduleError::MissingKey(key.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn slot(end_time: u16, temperature: f64) -> Slot {
+        Slot {
+            end_time,
+            temperature,
+        }
+    }
+
+    fn workday() -> Vec<Slot> {
+        vec![
+            slot(6 * 60, 17.0),
+            slot(22 * 60, 21.5),
+            slot(MINUTES_PER_DAY, 17.0),
+        ]
+    }
+
+    #[test]
+    fn paramset_round_trip() {
+        for format in [ScheduleFormat::BidCos, ScheduleFormat::HmIP { program: 2 }] {
+            let mut schedule = WeekSchedule::uniform(workday());
+            schedule.set_day(Weekday::Sunday, vec![slot(MINUTES_PER_DAY, 20.0)]);
+
+            let paramset = schedule.to_paramset(format);
+            assert_eq!(paramset.len(), 7 * 2 * format.max_slots());
+            assert_eq!(ScheduleFormat::detect(&paramset, 2), Some(format));
+            assert_eq!(
+                WeekSchedule::from_paramset(&paramset, format).unwrap(),
+                schedule
+            );
+        }
+    }
+
+    #[test]
+    fn unused_slots_are_filled() {
+        let paramset = WeekSchedule::uniform(workday()).to_paramset(ScheduleFormat::BidCos);
+        assert!(matches!(
+            paramset.get("ENDTIME_MONDAY_24"),
+            Some(Value::Int(1440))
+        ));
+        assert!(matches!(
+            paramset.get("TEMPERATURE_MONDAY_24"),
+            Some(Value::Double(value)) if *value == 17.0
+        ));
+    }
+
+    #[test]
+    fn from_paramset_errors() {
+        let format = ScheduleFormat::BidCos;
+        let mut paramset = WeekSchedule::uniform(workday()).to_paramset(format);
+        paramset.insert("ENDTIME_TUESDAY_1".to_string(), Value::Bool(true));
+        assert!(matches!(
+            WeekSchedule::from_paramset(&paramset, format),
+            Err(ScheduleError::UnexpectedValue(key)) if key == "ENDTIME_TUESDAY_1"
+        ));
+
+        paramset.remove("ENDTIME_TUESDAY_1");
+        assert!(matches!(
+            WeekSchedule::from_paramset(&paramset, format),
+            Err(ScheduleError::MissingKey(key)) if key == "ENDTIME_TUESDAY_1"
+        ));
+    }
+
+    #[test]
+    fn validate() {
+        let format = ScheduleFormat::HmIP { program: 1 };
+        assert!(WeekSchedule::uniform(workday()).validate(format).is_ok());
+
+        let invalid = [
+            (vec![], "day has no slots"),
+            (
+                (1..=14).map(|n| slot(n * 100, 20.0)).collect(),
+                "too many slots",
+            ),
+            (
+                vec![slot(362, 20.0), slot(MINUTES_PER_DAY, 20.0)],
+                "end time is not a multiple of 5 minutes",
+            ),
+            (
+                vec![
+                    slot(600, 20.0),
+                    slot(600, 20.0),
+                    slot(MINUTES_PER_DAY, 20.0),
+                ],
+                "end time is not after the previous slot",
+            ),
+            (
+                vec![slot(MINUTES_PER_DAY + 5, 20.0)],
+                "end time is after midnight",
+            ),
+            (vec![slot(600, 20.0)], "last slot does not end at midnight"),
+            (
+                vec![slot(MINUTES_PER_DAY, 30.5)],
+                "temperature is out of range",
+            ),
+            (
+                vec![slot(MINUTES_PER_DAY, 4.5)],
+                "temperature is out of range",
+            ),
+            (
+                vec![slot(MINUTES_PER_DAY, 20.2)],
+                "temperature is not a multiple of 0.5 °C",
+            ),
+        ];
+        for (slots, expected) in invalid {
+            let mut schedule = WeekSchedule::uniform(workday());
+            schedule.set_day(Weekday::Wednesday, slots);
+            match schedule.validate(format) {
+                Err(ScheduleError::InvalidSlot { day, reason, .. }) => {
+                    assert_eq!(day, Weekday::Wednesday);
+                    assert_eq!(reason, expected);
+                }
+                other => panic!("expected {}, got {:?}", expected, other),
+            }
+        }
+    }
+
+    #[test]
+    fn bidcos_on_off() {
+        let mut schedule = WeekSchedule::uniform(workday());
+        schedule.set_day(
+            Weekday::Friday,
+            vec![slot(600, 4.5), slot(MINUTES_PER_DAY, 30.5)],
+        );
+        assert!(schedule.validate(ScheduleFormat::BidCos).is_ok());
+        assert!(schedule
+            .validate(ScheduleFormat::HmIP { program: 1 })
+            .is_err());
+    }
+
+    #[test]
+    fn weekday_keys() {
+        for day in Weekday::ALL {
+            assert_eq!(Weekday::from_key(&day.key().to_lowercase()), Some(day));
+        }
+        assert_eq!(Weekday::from_key("HOLIDAY"), None);
+    }
+}
@@ -0,0 +1,92 @@
+/// Methods of the HomeMatic XML-RPC interface specification.
+pub const DOCUMENTED_METHODS: &[&str] = &[
+    "abortDeleteDevice",
+    "activateLinkParamset",
+    "addDevice",
+    "addLink",
+    "addVirtualDeviceInstance",
+    "changeKey",
+    "clearConfigCache",
+    "deleteDevice",
+    "deleteVolatileMetadata",
+    "determineParameter",
+    "exit",
+    "getAllMetadata",
+    "getDeviceDescription",
+    "getInstallMode",
+    "getKeyMismatchDevice",
+    "getLinkInfo",
+    "getLinkPeers",
+    "getLinks",
+    "getMetadata",
+    "getParamset",
+    "getParamsetDescription",
+    "getParamsetId",
+    "getServiceMessages",
+    "getValue",
+    "getVersion",
+    "getVolatileMetadata",
+    "hasVolatileMetadata",
+    "init",
+    "listBidcosInterfaces",
+    "listDevices",
+    "listReplaceableDevices",
+    "listTeams",
+    "logLevel",
+    "ping",
+    "putParamset",
+    "refreshDeployedDeviceFirmwareList",
+    "removeLink",
+    "replaceDevice",
+    "reportValueUsage",
+    "restoreConfigToDevice",
+    "rssiInfo",
+    "setBidcosInterface",
+    "setInstallMode",
+    "setInterfaceClock",
+    "setLinkInfo",
+    "setMetadata",
+    "setRFLGWInfoLED",
+    "setTeam",
+    "setTempKey",
+    "setValue",
+    "setVolatileMetadata",
+    "updateFirmware",
+];
+
+/// Documented methods supported by an interface process.
+#[derive(Debug)]
+pub struct Capabilities {
+    /// Documented methods the interface process implements.
+    pub supported: Vec<&'static str>,
+
+    /// Documented methods the interface process does not implement.
+    pub unsupported: Vec<&'static str>,
+
+    /// Methods the interface process implements in addition to the documented ones.
+    pub additional: Vec<String>,
+}
+
+impl Capabilities {
+    pub(crate) fn from_methods(methods: Vec<String>) -> Self {
+        let (supported, unsupported) = DOCUMENTED_METHODS
+            .iter()
+            .partition(|name| methods.iter().any(|method| method == *name));
+        let additional = methods
+            .into_iter()
+            .filter(|method| !method.starts_with("system."))
+            .filter(|method| !DOCUMENTED_METHODS.contains(&method.as_str()))
+            .collect();
+
+        Self {
+            supported,
+            unsupported,
+            additional,
+        }
+    }
+
+    /// The interface process implements the method `name`.
+    pub fn supports(&self, name: &str) -> bool {
+        self.supported.contains(&name) || self.additional.iter().any(|method| method == name)
+    }
+}
@@ -0,0 +1,376 @@
+//! Rendering and parsing of parameter values with their unit.
+
+use std::borrow::Cow;
+use std::fmt;
+
+use xrs_xmlrpc::value::Value;
+
+use crate::ParameterDescription;
+
+/// Unit of a numeric parameter as reported in `UNIT`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Unit {
+    /// `100%`: the value ranges from 0.0 to 1.0 and is shown as percent (e.g. LEVEL).
+    Fraction,
+    Percent,
+    Celsius,
+    Seconds,
+    Minutes,
+    Other(String),
+}
+
+impl Unit {
+    /// Interpret a `UNIT` string, `None` for values without a unit.
+    pub fn parse(unit: &str) -> Option<Self> {
+        Some(match unit.trim() {
+            "" => return None,
+            "100%" => Unit::Fraction,
+            "%" => Unit::Percent,
+            // the degree sign is frequently sent in ISO-8859-1 and arrives mangled
+            "°C" | "\u{FFFD}C" | "degC" | "C" => Unit::Celsius,
+            "s" => Unit::Seconds,
+            "min" => Unit::Minutes,
+            other => Unit::Other(other.to_string()),
+        })
+    }
+
+    /// Symbol shown after the value.
+    pub fn symbol(&self) -> &str {
+        match self {
+            Unit::Fraction | Unit::Percent => "%",
+            Unit::Celsius => "°C",
+            Unit::Seconds => "s",
+            Unit::Minutes => "min",
+            Unit::Other(unit) => unit,
+        }
+    }
+
+    /// Factor from the displayed number to the parameter value.
+    fn scale(&self) -> f64 {
+        match self {
+            Unit::Fraction => 0.01,
+            _ => 1.0,
+        }
+    }
+
+    /// Factor converting a value given with `suffix` into this unit.
+    fn conversion(&self, suffix: &str) -> Option<f64> {
+        if suffix.is_empty() {
+            return Some(1.0);
+        }
+        if suffix == self.symbol() || Unit::parse(suffix).as_ref() == Some(self) {
+            return Some(self.scale());
+        }
+        let seconds = |unit: &str| match unit {
+            "s" => Some(1.0),
+            "min" => Some(60.0),
+            "h" => Some(3600.0),
+            _ => None,
+        };
+        match self {
+            Unit::Seconds | Unit::Minutes => Some(seconds(suffix)? / seconds(self.symbol())?),
+            _ => None,
+        }
+    }
+}
+
+/// Error of parsing a value from a string.
+#[derive(Debug)]
+pub struct ParseValueError(String);
+
+impl fmt::Display for ParseValueError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for ParseValueError {}
+
+impl ParameterDescription {
+    /// Unit of the parameter value.
+    pub fn parsed_unit(&self) -> Option<Unit> {
+        self.unit().and_then(Unit::parse)
+    }
+
+    /// Render a value for humans.
+    ///
+    /// Special values are shown by their id, numbers with their unit and enum values by label.
+    pub fn format_value(&self, value: &Value) -> String {
+        match (self, value) {
+            (ParameterDescription::Float(pd), Value::Double(v)) => {
+                if let Some(special) = pd.special.iter().flatten().find(|s| s.value == *v as f32) {
+                    return special.id.clone();
+                }
+                self.format_number(*v)
+            }
+            (ParameterDescription::Integer(pd), Value::Int(v)) => {
+                if let Some(special) = pd.special.iter().flatten().find(|s| s.value == *v) {
+                    return special.id.clone();
+                }
+                self.format_number(f64::from(*v))
+            }
+            (ParameterDescription::Enum(pd), Value::Int(v)) => pd
+                .label(*v)
+                .map(|label| label.to_string())
+                .unwrap_or_else(|| v.to_string()),
+            (_, Value::Int(v)) => v.to_string(),
+            (_, Value::Bool(v)) => v.to_string(),
+            (_, Value::Double(v)) => v.to_string(),
+            (_, Value::String(v)) => v.to_string(),
+            (_, other) => format!("{:?}", other),
+        }
+    }
+
+    /// Parse a value typed by a human.
+    ///
+    /// Accepts special value ids, numbers with or without unit (`50%` for a LEVEL, `2min` for a
+    /// time in seconds), enum indices and labels, and `on`/`off` style booleans. Numbers are
+    /// checked against MIN and MAX.
+    pub fn parse_value(&self, raw: &str) -> Result<Value<'static>, ParseValueError> {
+        let raw = raw.trim();
+        match self {
+            ParameterDescription::Float(pd) => {
+                if let Some(special) = pd.special.iter().flatten().find(|s| s.id == raw) {
+                    return Ok(Value::Double(f64::from(special.value)));
+                }
+                let value = self.parse_number(raw)?;
+                if value < f64::from(pd.min) || value > f64::from(pd.max) {
+                    return Err(out_of_range(raw, pd.min, pd.max));
+                }
+                Ok(Value::Double(value))
+            }
+            ParameterDescription::Integer(pd) => {
+                if let Some(special) = pd.special.iter().flatten().find(|s| s.id == raw) {
+                    return Ok(Value::Int(special.value));
+                }
+                let value = self.parse_number(raw)?;
+                if value.fract() != 0.0 {
+                    return Err(ParseValueError(format!("{} is not an integer", raw)));
+                }
+                if value < f64::from(pd.min) || value > f64::from(pd.max) {
+                    return Err(out_of_range(raw, pd.min, pd.max));
+                }
+                Ok(Value::Int(value as i32))
+            }
+            ParameterDescription::Bool(_) | ParameterDescription::Action(_) => {
+                match raw.to_ascii_lowercase().as_str() {
+                    "1" | "true" | "on" | "yes" => Ok(Value::Bool(true)),
+                    "0" | "false" | "off" | "no" => Ok(Value::Bool(false)),
+                    _ => Err(ParseValueError(format!("invalid boolean: {}", raw))),
+                }
+            }
+            ParameterDescription::Enum(pd) => {
+                let index = match raw.parse::<i32>() {
+                    Ok(index) => index,
+                    Err(_) => pd.index(raw).ok_or_else(|| {
+                        let labels: Vec<&str> = (pd.min..=pd.max)
+                            .filter_map(|index| pd.label(index))
+                            .collect();
+                        ParseValueError(format!("expected one of: {}", labels.join(", ")))
+                    })?,
+                };
+                if index < pd.min || index > pd.max {
+                    return Err(out_of_range(raw, pd.min, pd.max));
+                }
+                Ok(Value::Int(index))
+            }
+            ParameterDescription::String(_) => Ok(Value::String(Cow::Owned(raw.to_string()))),
+        }
+    }
+
+    fn format_number(&self, value: f64) -> String {
+        match self.parsed_unit() {
+            Some(unit @ (Unit::Fraction | Unit::Percent)) => {
+                format!("{}{}", trim_number(value / unit.scale()), unit.symbol())
+            }
+            Some(unit) => format!("{} {}", trim_number(value), unit.symbol()),
+            None => trim_number(value),
+        }
+    }
+
+    fn parse_number(&self, raw: &str) -> Result<f64, ParseValueError> {
+        let split = raw
+            .find(|c: char| !(c.is_ascii_digit() || matches!(c, '.' | '-' | '+')))
+            .unwrap_or(raw.len());
+        let (number, suffix) = raw.split_at(split);
+        let number: f64 = number
+            .parse()
+            .map_err(|_| ParseValueError(format!("invalid number: {}", raw)))?;
+
+        let factor = match self.parsed_unit() {
+            Some(unit) => unit.conversion(suffix.trim()),
+            None if suffix.trim().is_empty() => Some(1.0),
+            None => None,
+        };
+        factor
+            .map(|factor| number * factor)
+            .ok_or_else(|| ParseValueError(format!("unexpected unit: {}", suffix.trim())))
+    }
+}
+
+fn out_of_range(raw: &str, min: impl fmt::Display, max: impl fmt::Display) -> ParseValueError {
+    ParseValueError(format!("{} is out of range [{}, {}]", raw, min, max))
+}
+
+/// Print a number with at most two decimals.
+fn trim_number(value: f64) -> String {
+    let formatted = format!("{:.2}", value);
+    formatted
+        .trim_end_matches('0')
+        .trim_end_matches('.')
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn description(description: serde_json::Value) -> ParameterDescription {
+        serde_json::from_value(description).unwrap()
+    }
+
+    fn level() -> ParameterDescription {
+        description(json!({
+            "TYPE": "FLOAT", "OPERATIONS": 7, "FLAGS": 1,
+            "DEFAULT": 0.0, "MIN": 0.0, "MAX": 1.0, "UNIT": "100%",
+        }))
+    }
+
+    fn on_time() -> ParameterDescription {
+        description(json!({
+            "TYPE": "FLOAT", "OPERATIONS": 2, "FLAGS": 1,
+            "DEFAULT": 0.0, "MIN": 0.0, "MAX": 111600.0, "UNIT": "s",
+            "SPECIAL": [{"ID": "NOT_USED", "VALUE": 0.0}],
+        }))
+    }
+
+    fn double(value: Result<Value<'static>, ParseValueError>) -> f64 {
+        match value {
+            Ok(Value::Double(value)) => value,
+            other => panic!("expected a double, got {:?}", other),
+        }
+    }
+
+    fn int(value: Result<Value<'static>, ParseValueError>) -> i32 {
+        match value {
+            Ok(Value::Int(value)) => value,
+            other => panic!("expected an int, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn units() {
+        assert_eq!(Unit::parse(""), None);
+        assert_eq!(Unit::parse("100%"), Some(Unit::Fraction));
+        assert_eq!(Unit::parse("\u{FFFD}C"), Some(Unit::Celsius));
+        assert_eq!(Unit::parse("V"), Some(Unit::Other("V".to_string())));
+        assert_eq!(level().parsed_unit(), Some(Unit::Fraction));
+    }
+
+    #[test]
+    fn fraction() {
+        let level = level();
+        assert_eq!(level.format_value(&Value::Double(0.5)), "50%");
+        assert_eq!(level.format_value(&Value::Double(0.125)), "12.5%");
+        assert_eq!(double(level.parse_value("50%")), 0.5);
+        assert_eq!(double(level.parse_value(" 0.25 ")), 0.25);
+
+        for value in [0.0, 0.01, 0.33, 1.0] {
+            let formatted = level.format_value(&Value::Double(value));
+            assert!((double(level.parse_value(&formatted)) - value).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn time() {
+        let on_time = on_time();
+        assert_eq!(on_time.format_value(&Value::Double(120.0)), "120 s");
+        assert_eq!(double(on_time.parse_value("2min")), 120.0);
+        assert_eq!(double(on_time.parse_value("1.5 h")), 5400.0);
+        assert_eq!(double(on_time.parse_value("90s")), 90.0);
+    }
+
+    #[test]
+    fn special_values() {
+        let on_time = on_time();
+        assert_eq!(on_time.format_value(&Value::Double(0.0)), "NOT_USED");
+        assert_eq!(double(on_time.parse_value("NOT_USED")), 0.0);
+
+        let interval = description(json!({
+            "TYPE": "INTEGER", "OPERATIONS": 3, "FLAGS": 1,
+            "DEFAULT": 10, "MIN": 1, "MAX": 60, "UNIT": "min",
+            "SPECIAL": [{"ID": "OFF", "VALUE": 255}],
+        }));
+        assert_eq!(interval.format_value(&Value::Int(255)), "OFF");
+        assert_eq!(interval.format_value(&Value::Int(15)), "15 min");
+        assert_eq!(int(interval.parse_value("OFF")), 255);
+        assert_eq!(int(interval.parse_value("1h")), 60);
+    }
+
+    #[test]
+    fn enums_and_bools() {
+        let mode = description(json!({
+            "TYPE": "ENUM", "OPERATIONS": 7, "FLAGS": 1,
+            "DEFAULT": 0, "MIN": 0, "MAX": 3,
+            "VALUE_LIST": ["AUTO-MODE", "MANU-MODE", "PARTY-MODE", "BOOST-MODE"],
+        }));
+        assert_eq!(mode.format_value(&Value::Int(1)), "MANU-MODE");
+        assert_eq!(mode.format_value(&Value::Int(9)), "9");
+        assert_eq!(int(mode.parse_value("PARTY-MODE")), 2);
+        assert_eq!(int(mode.parse_value("3")), 3);
+
+        let state = description(json!({
+            "TYPE": "BOOL", "OPERATIONS": 7, "FLAGS": 1,
+            "DEFAULT": false, "MIN": false, "MAX": true,
+        }));
+        assert!(matches!(state.parse_value("On"), Ok(Value::Bool(true))));
+        assert!(matches!(state.parse_value("0"), Ok(Value::Bool(false))));
+        assert_eq!(state.format_value(&Value::Bool(true)), "true");
+    }
+
+    #[test]
+    fn errors() {
+        let error = |description: &ParameterDescription, raw: &str| {
+            description.parse_value(raw).unwrap_err().to_string()
+        };
+
+        assert_eq!(error(&level(), "150%"), "150% is out of range [0, 1]");
+        assert_eq!(error(&level(), "50"), "50 is out of range [0, 1]");
+        assert_eq!(error(&level(), "50°C"), "unexpected unit: °C");
+        assert_eq!(error(&level(), "half"), "invalid number: half");
+        assert_eq!(error(&on_time(), "-1s"), "-1s is out of range [0, 111600]");
+
+        let interval = description(json!({
+            "TYPE": "INTEGER", "OPERATIONS": 3, "FLAGS": 1,
+            "DEFAULT": 10, "MIN": 1, "MAX": 60, "UNIT": "min",
+        }));
+        assert_eq!(error(&interval, "2.5"), "2.5 is not an integer");
+        assert_eq!(error(&interval, "0"), "0 is out of range [1, 60]");
+
+        let mode = description(json!({
+            "TYPE": "ENUM", "OPERATIONS": 7, "FLAGS": 1,
+            "DEFAULT": 0, "MIN": 0, "MAX": 2,
+            "VALUE_LIST": ["AUTO", "", "MANU"],
+        }));
+        assert_eq!(error(&mode, "PARTY"), "expected one of: AUTO, MANU");
+        assert_eq!(error(&mode, "3"), "3 is out of range [0, 2]");
+
+        let state = description(json!({
+            "TYPE": "BOOL", "OPERATIONS": 7, "FLAGS": 1,
+            "DEFAULT": false, "MIN": false, "MAX": true,
+        }));
+        assert_eq!(error(&state, "maybe"), "invalid boolean: maybe");
+    }
+
+    #[test]
+    fn trim() {
+        assert_eq!(trim_number(0.0), "0");
+        assert_eq!(trim_number(20.0), "20");
+        assert_eq!(trim_number(100.0), "100");
+        assert_eq!(trim_number(21.5), "21.5");
+        assert_eq!(trim_number(0.126), "0.13");
+        assert_eq!(trim_number(-3.25), "-3.25");
+    }
+}